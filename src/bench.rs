@@ -0,0 +1,156 @@
+use std::{
+    fmt::{self, Write},
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::solver::Puzzle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part1",
+            Stage::Part2 => "part2",
+        })
+    }
+}
+
+/// Wall time statistics over repeated runs of one stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Computes the statistics of `samples`, using the nearest rank for the percentiles.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "Statistics need at least one sample.");
+        samples.sort();
+        let percentile = |p: usize| samples[(samples.len() * p).div_ceil(100).max(1) - 1];
+        Self {
+            runs: samples.len(),
+            min: samples[0],
+            median: percentile(50),
+            p95: percentile(95),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Measurement {
+    pub day: u8,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+fn sample<T>(runs: usize, mut f: impl FnMut() -> T) -> Stats {
+    Stats::from_samples(
+        (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                black_box(f());
+                start.elapsed()
+            })
+            .collect(),
+    )
+}
+
+/// Runs the generator and both parts of `puzzle` `runs` times each.
+pub fn bench(puzzle: &dyn Puzzle, input: &str, runs: usize) -> Result<Vec<Measurement>> {
+    let parsed = puzzle.parse(input)?;
+    let measurement = |stage, stats| Measurement {
+        day: puzzle.day(),
+        stage,
+        stats,
+    };
+    Ok(vec![
+        measurement(
+            Stage::Parse,
+            sample(runs, || puzzle.parse(black_box(input))),
+        ),
+        measurement(Stage::Part1, sample(runs, || puzzle.part1(&parsed))),
+        measurement(Stage::Part2, sample(runs, || puzzle.part2(&parsed))),
+    ])
+}
+
+/// Renders measurements as an aligned table for humans.
+pub fn table(measurements: &[Measurement]) -> String {
+    let mut table = format!(
+        "{:>3} {:<5} {:>6} {:>14} {:>14} {:>14}\n",
+        "day", "stage", "runs", "min", "median", "p95"
+    );
+    for Measurement { day, stage, stats } in measurements {
+        writeln!(
+            table,
+            "{:>3} {:<5} {:>6} {:>14} {:>14} {:>14}",
+            day,
+            stage.to_string(),
+            stats.runs,
+            format!("{:?}", stats.min),
+            format!("{:?}", stats.median),
+            format!("{:?}", stats.p95)
+        )
+        .unwrap();
+    }
+    table
+}
+
+/// Renders measurements as a JSON array with all times in nanoseconds, for comparing runs.
+pub fn json(measurements: &[Measurement]) -> String {
+    let rows = measurements
+        .iter()
+        .map(|Measurement { day, stage, stats }| {
+            format!(
+                r#"  {{"day": {}, "stage": "{}", "runs": {}, "min_ns": {}, "median_ns": {}, "p95_ns": {}}}"#,
+                day,
+                stage,
+                stats.runs,
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.p95.as_nanos()
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver;
+
+    #[test]
+    fn stats_use_nearest_rank() {
+        let stats = Stats::from_samples((1..=20).rev().map(Duration::from_millis).collect());
+        assert_eq!(stats.runs, 20);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(10));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+
+        let single = Stats::from_samples(vec![Duration::from_millis(3)]);
+        assert_eq!((single.median, single.p95), (single.min, single.min));
+    }
+
+    #[test]
+    fn benches_every_stage() {
+        let measurements = bench(solver::get(1).unwrap(), "L68\nR32", 3).unwrap();
+        assert_eq!(
+            measurements.iter().map(|m| m.stage).collect::<Vec<_>>(),
+            vec![Stage::Parse, Stage::Part1, Stage::Part2]
+        );
+        assert!(json(&measurements).contains(r#""day": 1, "stage": "part2", "runs": 3"#));
+    }
+}
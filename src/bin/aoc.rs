@@ -0,0 +1,404 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, Read},
+    path::Path,
+    process::ExitCode,
+};
+
+use anyhow::{Context, Result, bail};
+use aoc_2025::{
+    bench,
+    day1::{self, Dial},
+    day2::{self, Rule},
+    differential, generate,
+    runner::{self, Parts},
+    solver,
+    verify::{self, Answers},
+    visualize::{self, Picture, ppm},
+};
+
+const USAGE: &str = "Usage:
+  aoc <day> [1|2|all] [input|-]
+      Solves the given day with the input read from a file, or from stdin if
+      the path is `-` or missing. Prints every answer together with its run time.
+  aoc verify [--answers answers.toml] [--inputs input/2025] [--record]
+      Runs every day on its cached input and compares the results with the
+      recorded answers. With --record, answers that are missing are added.
+  aoc bench [day...] [--runs 100] [--inputs input/2025] [--json]
+      Runs the generator and both parts of the given days, or of every day with
+      a cached input, repeatedly and reports min, median and p95 wall time.
+  aoc generate <day> [--seed 0] [--size 10]
+      Prints a random input for the given day.
+  aoc diff [day...] [--seeds 20] [--size 6]
+      Compares the given days, or every day, with their brute-force reference
+      solutions on small random inputs and prints a minimal failing input.
+  aoc visualize <day> [input|-] [--out visualization] [--scale 4]
+      Draws days 4 and 7 as a sequence of PPM frames scaled up by the given
+      factor, and days 8, 9 and 12 as an SVG, into the output directory.
+  aoc trace [input|-] [--modulus 100] [--start 50] [--target 0]
+      Replays the day 1 rotations on the given dial and prints its position,
+      the clicks pointing at the target and both passwords after every step.
+  aoc stream [input|-] [--modulus 100] [--start 50] [--target 0]
+      Solves day 1 while reading one rotation at a time, for inputs too large
+      to hold in memory.
+  aoc report [input|-] [--part 2] [--radix 10] [--merge]
+      Lists the invalid day 2 IDs of every range with their repeated block, and
+      the count and sum per range. With --merge, overlapping ranges are merged
+      first so that no ID is listed twice.";
+
+fn read_input(path: Option<&str>) -> Result<String> {
+    match path {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read input from stdin")?;
+            Ok(input)
+        }
+        Some(path) => fs::read_to_string(path).with_context(|| format!("Failed to read {path}")),
+    }
+}
+
+fn solve(args: &[String]) -> Result<ExitCode> {
+    let (day, rest) = match args {
+        [day, rest @ ..] if rest.len() <= 2 => (day, rest),
+        _ => bail!("{USAGE}"),
+    };
+    let day = day
+        .parse::<u8>()
+        .with_context(|| format!("Day {day:?} is not a number"))?;
+    let parts = rest
+        .first()
+        .map(|parts| parts.parse::<Parts>())
+        .transpose()?
+        .unwrap_or(Parts::All);
+    let input = read_input(rest.get(1).map(String::as_str))?;
+
+    let solution = runner::solve(day, parts, input.trim_end_matches('\n'))?;
+    println!(
+        "Day {} - generator: {:?}",
+        solution.day, solution.parse_time
+    );
+    for answer in solution.answers {
+        println!(
+            "Day {} - part {}: {} ({:?})",
+            solution.day, answer.part, answer.value, answer.elapsed
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn verify(args: &[String]) -> Result<ExitCode> {
+    let mut answers_path = "answers.toml";
+    let mut input_dir = "input/2025";
+    let mut record = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => answers_path = args.next().context("--answers needs a path")?,
+            "--inputs" => input_dir = args.next().context("--inputs needs a directory")?,
+            "--record" => record = true,
+            _ => bail!("Unknown argument {arg:?}\n\n{USAGE}"),
+        }
+    }
+
+    let answers_path = Path::new(answers_path);
+    let mut answers = Answers::load(answers_path)?;
+    let checks = verify::verify(&mut answers, Path::new(input_dir), record);
+    println!("{}", verify::report(&checks));
+    if record {
+        answers.save(answers_path)?;
+    }
+
+    if checks.iter().any(|check| check.is_failure()) {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn bench(args: &[String]) -> Result<ExitCode> {
+    let mut days = Vec::new();
+    let mut runs = 100;
+    let mut input_dir = "input/2025";
+    let mut json = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                runs = args
+                    .next()
+                    .context("--runs needs a count")?
+                    .parse()
+                    .context("--runs is not a number")?;
+                if runs == 0 {
+                    bail!("--runs needs to be at least 1");
+                }
+            }
+            "--inputs" => input_dir = args.next().context("--inputs needs a directory")?,
+            "--json" => json = true,
+            day => days.push(
+                day.parse::<u8>()
+                    .with_context(|| format!("Unknown argument {day:?}\n\n{USAGE}"))?,
+            ),
+        }
+    }
+    let explicit = !days.is_empty();
+    if !explicit {
+        days = solver::days().collect();
+    }
+
+    let mut measurements = Vec::new();
+    for day in days {
+        let puzzle = solver::get(day).with_context(|| format!("No solution for day {day}"))?;
+        let path = Path::new(input_dir).join(format!("day{day}.txt"));
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(_) if !explicit => {
+                eprintln!("Skipping day {day}, no input at {}", path.display());
+                continue;
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()));
+            }
+        };
+        measurements.extend(bench::bench(puzzle, input.trim_end_matches('\n'), runs)?);
+    }
+
+    if json {
+        println!("{}", bench::json(&measurements));
+    } else {
+        print!("{}", bench::table(&measurements));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn generate(args: &[String]) -> Result<ExitCode> {
+    let (day, rest) = args.split_first().with_context(|| USAGE.to_owned())?;
+    let day = day
+        .parse::<u8>()
+        .with_context(|| format!("Day {day:?} is not a number"))?;
+    let mut seed = 0;
+    let mut size = 10;
+    let mut args = rest.iter();
+    while let Some(arg) = args.next() {
+        let value = |name: &str, value: Option<&String>| -> Result<u64> {
+            value
+                .with_context(|| format!("{name} needs a number"))?
+                .parse()
+                .with_context(|| format!("{name} is not a number"))
+        };
+        match arg.as_str() {
+            "--seed" => seed = value("--seed", args.next())?,
+            "--size" => size = value("--size", args.next())? as usize,
+            _ => bail!("Unknown argument {arg:?}\n\n{USAGE}"),
+        }
+    }
+
+    let input = generate::generate(day, seed, size)
+        .with_context(|| format!("No generator for day {day}"))?;
+    println!("{input}");
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(args: &[String]) -> Result<ExitCode> {
+    let mut days = Vec::new();
+    let mut seeds = 20;
+    let mut size = 6;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = |name: &str, value: Option<&String>| -> Result<u64> {
+            value
+                .with_context(|| format!("{name} needs a number"))?
+                .parse()
+                .with_context(|| format!("{name} is not a number"))
+        };
+        match arg.as_str() {
+            "--seeds" => seeds = value("--seeds", args.next())?,
+            "--size" => size = value("--size", args.next())? as usize,
+            day => days.push(
+                day.parse::<u8>()
+                    .with_context(|| format!("Unknown argument {day:?}\n\n{USAGE}"))?,
+            ),
+        }
+    }
+    if days.is_empty() {
+        days = solver::days().collect();
+    }
+
+    let mut agree = true;
+    for day in days {
+        match differential::run(day, seeds, size) {
+            Some(disagreement) => {
+                println!("{disagreement}\n");
+                agree = false;
+            }
+            None => println!("Day {day}: agrees with the reference"),
+        }
+    }
+
+    if agree {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn visualize(args: &[String]) -> Result<ExitCode> {
+    let (day, rest) = args.split_first().with_context(|| USAGE.to_owned())?;
+    let day = day
+        .parse::<u8>()
+        .with_context(|| format!("Day {day:?} is not a number"))?;
+    let mut input_path = None;
+    let mut out_dir = "visualization";
+    let mut scale = 4;
+    let mut args = rest.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out_dir = args.next().context("--out needs a directory")?,
+            "--scale" => {
+                scale = args
+                    .next()
+                    .context("--scale needs a factor")?
+                    .parse()
+                    .context("--scale is not a number")?
+            }
+            path if input_path.is_none() => input_path = Some(path),
+            _ => bail!("Unknown argument {arg:?}\n\n{USAGE}"),
+        }
+    }
+    let input = read_input(input_path)?;
+
+    let out_dir = Path::new(out_dir);
+    fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    let files = match visualize::visualize(day, input.trim_end_matches('\n'))? {
+        Picture::Svg(svg) => vec![(format!("day{day}.svg"), svg.to_string().into_bytes())],
+        Picture::Frames(frames) => frames
+            .iter()
+            .enumerate()
+            .map(|(frame, image)| {
+                (
+                    format!("day{day}-{frame:04}.ppm"),
+                    ppm::encode(image, scale),
+                )
+            })
+            .collect(),
+    };
+    for (name, contents) in &files {
+        let path = out_dir.join(name);
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    println!("Wrote {} file(s) to {}", files.len(), out_dir.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parses an optional input path and the options of a day 1 dial.
+fn dial_args(args: &[String]) -> Result<(Option<&str>, Dial)> {
+    let mut input_path = None;
+    let (mut modulus, mut start, mut target) = (100, 50, 0);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = |name: &str, value: Option<&String>| -> Result<i64> {
+            value
+                .with_context(|| format!("{name} needs a number"))?
+                .parse()
+                .with_context(|| format!("{name} is not a number"))
+        };
+        match arg.as_str() {
+            "--modulus" => modulus = value("--modulus", args.next())?,
+            "--start" => start = value("--start", args.next())?,
+            "--target" => target = value("--target", args.next())?,
+            path if input_path.is_none() => input_path = Some(path),
+            _ => bail!("Unknown argument {arg:?}\n\n{USAGE}"),
+        }
+    }
+    if modulus <= 0 {
+        bail!("--modulus needs to be at least 1");
+    }
+    Ok((input_path, Dial::new(modulus, start, target)))
+}
+
+fn trace(args: &[String]) -> Result<ExitCode> {
+    let (input_path, dial) = dial_args(args)?;
+    let input = read_input(input_path)?;
+
+    let rotations = day1::parse(input.trim_end_matches('\n'))?;
+    let steps = day1::trace(dial, &rotations);
+    print!("{}", day1::render_trace(&steps));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn stream(args: &[String]) -> Result<ExitCode> {
+    let (input_path, dial) = dial_args(args)?;
+    let passwords = match input_path {
+        None | Some("-") => day1::solve_stream(dial, io::stdin().lock())?,
+        Some(path) => {
+            let file = File::open(path).with_context(|| format!("Failed to read {path}"))?;
+            day1::solve_stream(dial, BufReader::new(file))?
+        }
+    };
+    println!("Day 1 - part 1: {}", passwords.part1);
+    println!("Day 1 - part 2: {}", passwords.part2);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn report(args: &[String]) -> Result<ExitCode> {
+    let mut input_path = None;
+    let (mut part, mut radix, mut merge) = (2, 10, false);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = |name: &str, value: Option<&String>| -> Result<u32> {
+            value
+                .with_context(|| format!("{name} needs a number"))?
+                .parse()
+                .with_context(|| format!("{name} is not a number"))
+        };
+        match arg.as_str() {
+            "--part" => part = value("--part", args.next())?,
+            "--radix" => radix = value("--radix", args.next())?,
+            "--merge" => merge = true,
+            path if input_path.is_none() => input_path = Some(path),
+            _ => bail!("Unknown argument {arg:?}\n\n{USAGE}"),
+        }
+    }
+    if !(2..=36).contains(&radix) {
+        bail!("--radix needs to be within 2 to 36");
+    }
+    let rule = match part {
+        1 => Rule::new(radix, 2, 2, Some(2))?,
+        2 => Rule::new(radix, 2, u32::MAX, None)?,
+        _ => bail!("--part needs to be 1 or 2"),
+    };
+    let input = read_input(input_path)?;
+
+    let mut ranges = day2::parse_radix(input.trim_end_matches('\n'), radix)?;
+    if merge {
+        ranges = day2::merge(&ranges);
+    }
+    print!("{}", day2::render_report(&ranges, rule));
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run(args: &[String]) -> Result<ExitCode> {
+    match args.first().map(String::as_str) {
+        Some("verify") => verify(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("visualize") => visualize(&args[1..]),
+        Some("trace") => trace(&args[1..]),
+        Some("stream") => stream(&args[1..]),
+        Some("report") => report(&args[1..]),
+        _ => solve(args),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    run(&args).unwrap_or_else(|err| {
+        eprintln!("{err:#}");
+        ExitCode::FAILURE
+    })
+}
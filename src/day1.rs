@@ -1,83 +1,362 @@
-use std::str::FromStr;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    io::BufRead,
+    ops::RangeInclusive,
+    str::FromStr,
+};
 
-use anyhow::{Context, bail};
+use anyhow::{Context, Result};
 use aoc_runner_derive::{aoc, aoc_generator};
+use num::{BigInt, Integer, ToPrimitive, Zero};
 
-type Input = Vec<Direction>;
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
 
-#[derive(Debug)]
-enum Direction {
-    Right(i64),
-    Left(i64),
+pub type Input = Vec<Direction>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Right(BigInt),
+    Left(BigInt),
 }
 
 impl FromStr for Direction {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.chars().collect::<Vec<char>>();
-        let clicks = s[1..]
-            .iter()
-            .collect::<String>()
-            .parse()
-            .context("Failed to parse number")?;
-        match s[0] {
-            'L' => Ok(Direction::Left(clicks)),
-            'R' => Ok(Direction::Right(clicks)),
-            _ => bail!("Unknown prefix"),
+        let parser = Parser::new(Day1::DAY, s);
+        let Some(prefix) = s.chars().next() else {
+            return Err(parser.error(s, "Empty rotation"));
+        };
+        let (prefix, clicks) = s.split_at(prefix.len_utf8());
+        let clicks = parser.number(clicks, "Failed to parse number")?;
+        match prefix {
+            "L" => Ok(Direction::Left(clicks)),
+            "R" => Ok(Direction::Right(clicks)),
+            _ => Err(parser.error(prefix, "Unknown prefix")),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Right(n) => write!(f, "R{n}"),
+            Direction::Left(n) => write!(f, "L{n}"),
         }
     }
 }
 
 #[aoc_generator(day1)]
-fn parse(input: &str) -> anyhow::Result<Input> {
-    input.lines().map(Direction::from_str).collect()
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    input
+        .lines()
+        .map(|line| Direction::from_str(line).map_err(|err| err.relative_to(input, line)))
+        .collect()
 }
 
-fn wrapping_add_range(lhs: i64, rhs: i64, max: i64) -> i64 {
-    (lhs + rhs).rem_euclid(max + 1)
+/// A safe dial with `modulus` positions, numbered from 0, counting how often it points at
+/// `target`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dial {
+    modulus: i64,
+    position: i64,
+    target: i64,
 }
 
-#[aoc(day1, part1)]
-fn part1(input: &Input) -> i64 {
-    let (password, _) = input.iter().fold((0, 50), |(mut password, dial), dir| {
-        let new_dial = match dir {
-            Direction::Right(n) => wrapping_add_range(dial, *n, 99),
-            Direction::Left(n) => wrapping_add_range(dial, -(*n), 99),
+impl Default for Dial {
+    /// The dial of the puzzle, going from 0 to 99, starting at 50 and looking for 0.
+    fn default() -> Self {
+        Self::new(100, 50, 0)
+    }
+}
+
+impl Dial {
+    /// Creates a dial pointing at `start`. Positions outside of the dial wrap around.
+    pub fn new(modulus: i64, start: i64, target: i64) -> Self {
+        assert!(modulus > 0, "A dial needs at least one position.");
+        Self {
+            modulus,
+            position: start.rem_euclid(modulus),
+            target: target.rem_euclid(modulus),
+        }
+    }
+
+    pub fn modulus(&self) -> i64 {
+        self.modulus
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub fn target(&self) -> i64 {
+        self.target
+    }
+
+    pub fn at_target(&self) -> bool {
+        self.position == self.target
+    }
+
+    /// Counts how many clicks of `direction` point the dial at `mark`, without turning it.
+    ///
+    /// Exact for any number of clicks, as only the clicks up to the first hit are stepped
+    /// through and the remaining ones are full turns.
+    pub fn crossings(&self, direction: &Direction, mark: i64) -> BigInt {
+        let (clicks, first_hit) = match direction {
+            Direction::Right(n) => (n, mark - self.position),
+            Direction::Left(n) => (n, self.position - mark),
         };
-        if new_dial == 0 {
-            password += 1;
+        // A dial already pointing at the mark needs a full turn to get back to it.
+        let first_hit = match first_hit.rem_euclid(self.modulus) {
+            0 => self.modulus,
+            clicks => clicks,
+        };
+        if *clicks < BigInt::from(first_hit) {
+            BigInt::zero()
+        } else {
+            (clicks - first_hit) / self.modulus + 1
         }
-        (password, new_dial)
-    });
-    password
+    }
+
+    /// Turns the dial and returns how many of its clicks pointed it at the target.
+    pub fn rotate(&mut self, direction: &Direction) -> BigInt {
+        let passes = self.crossings(direction, self.target);
+        let (clicks, sign) = match direction {
+            Direction::Right(n) => (n, 1),
+            Direction::Left(n) => (n, -1),
+        };
+        let clicks = clicks
+            .mod_floor(&BigInt::from(self.modulus))
+            .to_i64()
+            .expect("The remainder is smaller than the modulus.");
+        self.position = (self.position + sign * clicks).rem_euclid(self.modulus);
+        passes
+    }
 }
 
-#[aoc(day1, part2)]
-fn part2(input: &Input) -> i64 {
-    let (password, _) = input
+/// Counts the rotations that leave `dial` pointing at its target.
+pub fn part1_with(mut dial: Dial, input: &Input) -> i64 {
+    input
         .iter()
-        .fold((0, 50), |(mut password, dial), dir| match dir {
-            Direction::Right(n) => {
-                password += n / 100;
-                let leftover = n % 100;
-                let new_dial = wrapping_add_range(dial, leftover, 99);
-                if new_dial < dial || new_dial == 0 {
-                    password += 1;
-                }
-                (password, new_dial)
+        .filter(|dir| {
+            dial.rotate(dir);
+            dial.at_target()
+        })
+        .count() as i64
+}
+
+/// Counts every click that points `dial` at its target.
+pub fn part2_with(mut dial: Dial, input: &Input) -> BigInt {
+    input.iter().map(|dir| dial.rotate(dir)).sum()
+}
+
+/// The passwords of both parts, as computed by [`solve_stream`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Passwords {
+    pub part1: i64,
+    pub part2: BigInt,
+}
+
+/// Solves both parts while reading one rotation at a time from `reader`, so memory stays
+/// constant however long the input is.
+///
+/// Malformed rotations are reported as a [`ParseError`] with their line number.
+pub fn solve_stream(mut dial: Dial, mut reader: impl BufRead) -> Result<Passwords> {
+    let mut passwords = Passwords {
+        part1: 0,
+        part2: BigInt::zero(),
+    };
+    let mut line = String::new();
+    for number in 1.. {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .with_context(|| format!("Failed to read line {number}"))?;
+        if read == 0 {
+            break;
+        }
+        let rotation = line.trim_end_matches(['\n', '\r']);
+        let direction = Direction::from_str(rotation).map_err(|mut err| {
+            err.line = number;
+            err
+        })?;
+        passwords.part2 += dial.rotate(&direction);
+        if dial.at_target() {
+            passwords.part1 += 1;
+        }
+    }
+    Ok(passwords)
+}
+
+/// One rotation of a dial, as replayed by [`trace`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step<'a> {
+    pub direction: &'a Direction,
+    pub before: i64,
+    pub after: i64,
+    /// Clicks that pointed the dial at its target, including the last one.
+    pub passes: BigInt,
+    pub landed: bool,
+}
+
+/// Replays every rotation of `input` on `dial`.
+pub fn trace(mut dial: Dial, input: &Input) -> Vec<Step<'_>> {
+    input
+        .iter()
+        .map(|direction| {
+            let before = dial.position();
+            let passes = dial.rotate(direction);
+            Step {
+                direction,
+                before,
+                after: dial.position(),
+                passes,
+                landed: dial.at_target(),
             }
-            Direction::Left(n) => {
-                password += n / 100;
-                let leftover = n % 100;
-                let new_dial = wrapping_add_range(dial, -leftover, 99);
-                if dial != 0 && (new_dial > dial || new_dial == 0) {
-                    password += 1;
-                }
-                (password, new_dial)
+        })
+        .collect()
+}
+
+/// Renders a trace as a table, with the running totals of both parts in the last columns.
+pub fn render_trace(steps: &[Step]) -> String {
+    let mut table = format!(
+        "{:>6} {:>10} {:>6} {:>6} {:>8} {:>6} {:>8} {:>10}\n",
+        "step", "rotation", "before", "after", "passes", "landed", "part1", "part2"
+    );
+    let (mut part1, mut part2) = (0, BigInt::zero());
+    for (pos, step) in steps.iter().enumerate() {
+        part1 += usize::from(step.landed);
+        part2 += &step.passes;
+        writeln!(
+            table,
+            "{:>6} {:>10} {:>6} {:>6} {:>8} {:>6} {:>8} {:>10}",
+            pos + 1,
+            step.direction.to_string(),
+            step.before,
+            step.after,
+            step.passes,
+            if step.landed { "yes" } else { "" },
+            part1,
+            part2
+        )
+        .unwrap();
+    }
+    table
+}
+
+/// How rotations add to the password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// Part 1, counting rotations that end at the target.
+    Landing,
+    /// Part 2, counting every click that points at the target.
+    Passing,
+}
+
+/// Finds all start positions of a dial with `modulus` positions looking for `target` from which
+/// `input` yields `password` under `rule`, as sorted and disjoint ranges.
+///
+/// The offset of the dial from its start after each rotation does not depend on the start. So
+/// each rotation adds to the password for a single arc of start positions, plus its full turns
+/// for every start, and sweeping over the ends of the arcs takes O(n log n) for n rotations,
+/// however large the dial is.
+pub fn starts_for(
+    modulus: i64,
+    target: i64,
+    input: &Input,
+    rule: Rule,
+    password: &BigInt,
+) -> Vec<RangeInclusive<i64>> {
+    let origin = Dial::new(modulus, 0, target);
+    let target = origin.target();
+    // Arcs of start positions, as first position and length, for which a rotation counts once.
+    let mut arcs = Vec::new();
+    let mut full_turns = BigInt::zero();
+    let mut dial = origin;
+    for direction in input {
+        let offset = dial.position();
+        dial.rotate(direction);
+        match rule {
+            Rule::Landing => arcs.push(((target - dial.position()).rem_euclid(modulus), 1)),
+            Rule::Passing => {
+                let (Direction::Right(clicks) | Direction::Left(clicks)) = direction;
+                let (turns, rest) = clicks.div_mod_floor(&BigInt::from(modulus));
+                let rest = rest
+                    .to_i64()
+                    .expect("The remainder is smaller than the modulus.");
+                full_turns += turns;
+                // The first hit lies within the rest of the clicks for the starts s with
+                // 1 <= target - offset - s <= rest when turning right, and with
+                // 1 <= s + offset - target <= rest when turning left.
+                let first = match direction {
+                    Direction::Right(_) => target - offset - rest,
+                    Direction::Left(_) => target - offset + 1,
+                };
+                arcs.push((first.rem_euclid(modulus), rest));
             }
-        });
-    password
+        }
+    }
+
+    let Some(needed) = (password - full_turns).to_i64() else {
+        return Vec::new();
+    };
+    let mut changes = BTreeMap::from([(0, 0), (modulus, 0)]);
+    for (start, length) in arcs.into_iter().filter(|&(_, length)| length > 0) {
+        *changes.entry(start).or_default() += 1;
+        if start + length <= modulus {
+            *changes.entry(start + length).or_default() -= 1;
+        } else {
+            *changes.entry(modulus).or_default() -= 1;
+            *changes.entry(0).or_default() += 1;
+            *changes.entry(start + length - modulus).or_default() -= 1;
+        }
+    }
+    let mut starts: Vec<RangeInclusive<i64>> = Vec::new();
+    let mut count = 0;
+    for ((&from, change), &to) in changes.iter().zip(changes.keys().skip(1)) {
+        count += change;
+        if count != needed {
+            continue;
+        }
+        match starts.last_mut() {
+            Some(last) if *last.end() + 1 == from => *last = *last.start()..=(to - 1),
+            _ => starts.push(from..=(to - 1)),
+        }
+    }
+    starts
+}
+
+#[aoc(day1, part1)]
+pub fn part1(input: &Input) -> i64 {
+    part1_with(Dial::default(), input)
+}
+
+#[aoc(day1, part2)]
+pub fn part2(input: &Input) -> BigInt {
+    part2_with(Dial::default(), input)
+}
+
+pub struct Day1;
+
+impl Solver for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Input;
+    type Part1 = i64;
+    type Part2 = BigInt;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -124,7 +403,114 @@ L82"
                 )
                 .unwrap()
             ),
-            6
+            BigInt::from(6)
+        );
+    }
+
+    /// A right rotation by a whole number of turns that ends where it started used to count
+    /// its last click on 0 twice, once as a full turn and once as the leftover landing on 0.
+    #[test]
+    fn part2_full_turns_from_zero() {
+        assert_eq!(part2(&parse("R50\nR100\nL200").unwrap()), BigInt::from(4));
+    }
+
+    #[test]
+    fn custom_dial() {
+        let input = parse("R3\nL10\nR7").unwrap();
+        // 8 positions starting at 2 and looking for 5: 2 -> 5 -> 3 -> 2.
+        let dial = Dial::new(8, 2, 5);
+        assert_eq!(part1_with(dial, &input), 1);
+        assert_eq!(part2_with(dial, &input), BigInt::from(1 + 1 + 1));
+    }
+
+    #[test]
+    fn huge_rotations() {
+        let clicks = "1".repeat(30);
+        let input = parse(&format!("R{clicks}\nL{clicks}")).unwrap();
+        // 111...1 clicks from 50 pass 0 after 50 clicks and then every 100 more; the dial stops
+        // at 61, so turning back passes 0 after 61 clicks.
+        let turns = |first_hit: &str| {
+            (clicks.parse::<BigInt>().unwrap() - first_hit.parse::<BigInt>().unwrap()) / 100 + 1
+        };
+        assert_eq!(part2(&input), turns("50") + turns("61"));
+        assert_eq!(part1(&input), 0);
+
+        let dial = Dial::default();
+        assert_eq!(dial.crossings(&input[0], 61), turns("11"));
+    }
+
+    #[test]
+    fn traces_rotations() {
+        let input = parse("R50\nL5\nR205").unwrap();
+        let steps = trace(Dial::default(), &input);
+        assert_eq!(
+            steps
+                .iter()
+                .map(|step| (step.before, step.after, step.passes.clone(), step.landed))
+                .collect::<Vec<_>>(),
+            [
+                (50, 0, BigInt::from(1), true),
+                (0, 95, BigInt::zero(), false),
+                (95, 0, BigInt::from(3), true),
+            ]
+        );
+        let table = render_trace(&steps);
+        assert_eq!(table.lines().count(), 4);
+        assert!(
+            table
+                .lines()
+                .last()
+                .unwrap()
+                .ends_with("R205     95      0        3    yes        2          4")
         );
     }
+
+    #[test]
+    fn finds_start_positions() {
+        let input = parse("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\nR250\nL7").unwrap();
+        for (modulus, target) in [(100, 0), (7, 3), (1, 0), (13, 12)] {
+            for rule in [Rule::Landing, Rule::Passing] {
+                let simulate = |start| {
+                    let dial = Dial::new(modulus, start, target);
+                    match rule {
+                        Rule::Landing => BigInt::from(part1_with(dial, &input)),
+                        Rule::Passing => part2_with(dial, &input),
+                    }
+                };
+                for password in (0..=12).map(BigInt::from) {
+                    let expected = (0..modulus)
+                        .filter(|&start| simulate(start) == password)
+                        .collect::<Vec<_>>();
+                    let found = starts_for(modulus, target, &input, rule, &password);
+                    assert_eq!(
+                        found.into_iter().flatten().collect::<Vec<_>>(),
+                        expected,
+                        "{modulus} positions, target {target}, {rule:?}, password {password}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn streams_rotations() {
+        let passwords = solve_stream(Dial::default(), "L68\r\nL30\nR48\nL5\nR60\n".as_bytes());
+        assert_eq!(
+            passwords.unwrap(),
+            Passwords {
+                part1: 1,
+                part2: BigInt::from(3)
+            }
+        );
+
+        let err = solve_stream(Dial::default(), "L68\nL30\nX48\n".as_bytes()).unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 1, "X"));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = parse("L68\nR4x\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "4x"));
+    }
 }
@@ -1,4 +1,3 @@
-use anyhow::{Context, Result};
 use std::{
     collections::{HashSet, VecDeque},
     str::FromStr,
@@ -7,39 +6,55 @@ use std::{
 use aoc_runner_derive::{aoc, aoc_generator};
 use microlp::{ComparisonOp, OptimizationDirection, Problem};
 
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
 #[derive(Debug)]
-struct Machine {
+pub struct Machine {
     target_lights: usize,
     buttons: Vec<usize>,
     joltages: Vec<usize>,
 }
 
 impl FromStr for Machine {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let re = regex!(r"\[([.#]+)\] ((?:\((?:\d,?)+\) ?)+)\{(?<joltage>(?:\d,?)+)\}");
-        let captured = re.captures(s).context("Regex not capturing")?;
+        let parser = Parser::new(Day10::DAY, s);
+        let tokens = s.split_whitespace().collect::<Vec<_>>();
+        let &[lights, ref buttons @ .., joltages] = tokens.as_slice() else {
+            return Err(parser.missing(s, "Machine without joltages"));
+        };
+        let lights = parser.enclosed(lights, '[', ']')?;
+        let mut target_lights = 0;
+        for (pos, (offset, c)) in lights.char_indices().enumerate() {
+            match c {
+                '.' => {}
+                '#' => target_lights |= 1 << pos,
+                _ => return Err(parser.error(&lights[offset..][..c.len_utf8()], "Unknown light")),
+            }
+        }
         Ok(Machine {
-            target_lights: captured[1].chars().enumerate().fold(0, |acc, (pos, c)| {
-                acc | ((if c == '.' { 0 } else { 1 }) << pos)
-            }),
-            buttons: captured[2]
-                .split_whitespace()
-                .map(|buttons| {
-                    buttons[1..(buttons.len() - 1)]
-                        .split(',')
-                        .map(|n| n.parse::<usize>().unwrap())
-                        .fold(0, |acc, n| acc | (1 << n))
+            target_lights,
+            buttons: buttons
+                .iter()
+                .map(|button| {
+                    let lights = parser.enclosed(button, '(', ')')?;
+                    parser
+                        .numbers::<u32>(lights, ",", "Button not a number")?
+                        .into_iter()
+                        .try_fold(0, |acc, light| {
+                            let light = 1usize
+                                .checked_shl(light)
+                                .ok_or_else(|| parser.error(button, "Button light out of range"))?;
+                            Ok(acc | light)
+                        })
                 })
-                .collect(),
-            joltages: captured
-                .name("joltage")
-                .expect("No joltages captured.")
-                .as_str()
-                .split(',')
-                .map(|n| n.parse::<usize>().expect("Joltage not a number"))
-                .collect(),
+                .collect::<Result<_, _>>()?,
+            joltages: parser.numbers(
+                parser.enclosed(joltages, '{', '}')?,
+                ",",
+                "Joltage not a number",
+            )?,
         })
     }
 }
@@ -65,15 +80,15 @@ fn bfs(machine: &Machine) -> usize {
 }
 
 #[aoc_generator(day10)]
-fn parse(input: &str) -> Result<Vec<Machine>> {
+pub fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
     input
         .lines()
-        .map(Machine::from_str)
-        .collect::<Result<Vec<_>>>()
+        .map(|line| Machine::from_str(line).map_err(|err| err.relative_to(input, line)))
+        .collect::<Result<Vec<_>, _>>()
 }
 
 #[aoc(day10, part1)]
-fn part1(input: &[Machine]) -> usize {
+pub fn part1(input: &[Machine]) -> usize {
     input.iter().map(bfs).sum()
 }
 
@@ -109,10 +124,32 @@ fn solve(machine: &Machine) -> f64 {
 }
 
 #[aoc(day10, part2)]
-fn part2(input: &[Machine]) -> f64 {
+pub fn part2(input: &[Machine]) -> f64 {
     input.iter().map(solve).sum()
 }
 
+pub struct Day10;
+
+impl Solver for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Machine>;
+    type Part1 = usize;
+    type Part2 = f64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,6 +7,8 @@ use aoc_runner_derive::{aoc, aoc_generator};
 use memoize::memoize;
 use pathfinding::prelude::yen;
 
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
 fn compute_hash(inp: &str) -> u64 {
     let mut state = DefaultHasher::new();
     inp.hash(&mut state);
@@ -14,22 +16,23 @@ fn compute_hash(inp: &str) -> u64 {
 }
 
 #[aoc_generator(day11)]
-fn parse(input: &str) -> HashMap<u64, Vec<u64>> {
+pub fn parse(input: &str) -> Result<HashMap<u64, Vec<u64>>, ParseError> {
+    let parser = Parser::new(Day11::DAY, input);
     input
         .lines()
         .map(|line| {
-            let (device, connections) = line.split_once(": ").unwrap();
+            let (device, connections) = parser.key_words(line)?;
             let connections = connections
-                .split_whitespace()
-                .map(compute_hash)
+                .iter()
+                .map(|connection| compute_hash(connection))
                 .collect::<Vec<_>>();
-            (compute_hash(device), connections)
+            Ok((compute_hash(device), connections))
         })
-        .collect::<HashMap<_, _>>()
+        .collect::<Result<HashMap<_, _>, _>>()
 }
 
 #[aoc(day11, part1)]
-fn part1(input: &HashMap<u64, Vec<u64>>) -> usize {
+pub fn part1(input: &HashMap<u64, Vec<u64>>) -> usize {
     yen(
         &compute_hash("you"),
         |cur| {
@@ -60,17 +63,42 @@ fn dfs(start: u64, end: u64, connections: &HashMap<u64, Vec<u64>>) -> usize {
 }
 
 #[aoc(day11, part2)]
-fn part2(input: &HashMap<u64, Vec<u64>>) -> usize {
+pub fn part2(input: &HashMap<u64, Vec<u64>>) -> usize {
     let svr = compute_hash("svr");
     let dac = compute_hash("dac");
     let fft = compute_hash("fft");
     let out = compute_hash("out");
 
+    // The cache ignores the connections, so results of a previous input must not be reused.
+    memoized_flush_dfs();
+
     // Faster version thanks to https://www.reddit.com/r/adventofcode/comments/1pjp1rm/2025_day_11_solutions/ntf4e0t/
     dfs(svr, dac, input) * dfs(dac, fft, input) * dfs(fft, out, input)
         + dfs(svr, fft, input) * dfs(fft, dac, input) * dfs(dac, out, input)
 }
 
+pub struct Day11;
+
+impl Solver for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = HashMap<u64, Vec<u64>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,8 +106,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "aaa: you hhh
+            part1(
+                &parse(
+                    "aaa: you hhh
 you: bbb ccc
 bbb: ddd eee
 ccc: ddd eee fff
@@ -90,16 +119,28 @@ ggg: out
 hhh: ccc fff iii
 iii: out
 "
-            )),
+                )
+                .unwrap()
+            ),
             5
         );
     }
 
+    #[test]
+    fn part2_does_not_reuse_previous_input() {
+        let one = parse("svr: dac\ndac: fft\nfft: out").unwrap();
+        let two = parse("svr: dac\ndac: fft aaa\naaa: fft\nfft: out").unwrap();
+        assert_eq!(part2(&one), 1);
+        assert_eq!(part2(&two), 2);
+        assert_eq!(part2(&one), 1);
+    }
+
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "svr: aaa bbb
+            part2(
+                &parse(
+                    "svr: aaa bbb
 aaa: fft
 fft: ccc
 bbb: tty
@@ -113,7 +154,9 @@ fff: ggg hhh
 ggg: out
 hhh: out
 "
-            )),
+                )
+                .unwrap()
+            ),
             2
         );
     }
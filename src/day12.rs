@@ -1,72 +1,285 @@
+use std::collections::HashMap;
+
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
+
+use crate::{
+    error::ParseError,
+    solver::Solver,
+    utils::{grid::Grid, parse::Parser},
+};
 
-type Input = (Vec<Vec<Vec<bool>>>, Vec<((usize, usize), Vec<usize>)>);
+pub type Input = (Vec<Grid<bool>>, Vec<((usize, usize), Vec<usize>)>);
 
 /// See https://itp.tugraz.at/~06nuss/content/20110306_Graz.pdf
 #[aoc_generator(day12)]
-fn parse(input: &str) -> Input {
-    let splits = input.splitn(7, "\n\n").collect::<Vec<_>>();
-    let shapes = splits
+pub fn parse(input: &str) -> Result<Input, ParseError> {
+    let parser = Parser::new(Day12::DAY, input);
+    let splits = parser.sections(input, 7).collect::<Vec<_>>();
+    let shapes = splits[..(splits.len() - 1)]
         .iter()
-        .take(5)
         .map(|shape_spec| {
-            shape_spec
-                .lines()
-                .skip(1)
-                .map(|line| line.chars().map(|c| c == '#').collect::<Vec<_>>())
-                .collect::<Vec<_>>()
+            let shape = shape_spec.split_once('\n').map_or("", |(_, shape)| shape);
+            Grid::parse(Day12::DAY, shape, |c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            })
+            .map_err(|err| err.relative_to(input, shape))
         })
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?;
     let regions = splits
         .last()
-        .expect("No last element in input split.")
+        .expect("split always yields at least one element.")
         .lines()
         .map(|line| {
-            let (shape, shape_count) = line.split_once(": ").expect("No : in input line");
-            let (x, y) = shape.split_once('x').expect("No dimension separator.");
-            let x = x.parse::<usize>().expect("x dimension not a number.");
-            let y = y.parse::<usize>().expect("y dimension not a number.");
-            (
-                (x, y),
-                shape_count
-                    .split(' ')
-                    .map(|n| n.parse::<usize>().unwrap())
-                    .collect::<Vec<_>>(),
-            )
+            let (size, presents) = parser.key_values(line, "Shape count not a number.")?;
+            let [x, y] = parser.tuple(size, "x")?;
+            Ok(((x, y), presents))
         })
-        .collect::<Vec<_>>();
-    (shapes, regions)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((shapes, regions))
+}
+
+/// The cells of every distinct rotation and reflection of `shape`, as offsets from its first
+/// cell in row-major order.
+fn orientations(shape: &Grid<bool>) -> Vec<Vec<(isize, isize)>> {
+    let mut grids = Vec::new();
+    let mut grid = shape.clone();
+    for _ in 0..4 {
+        grids.push(grid.transpose());
+        grid = grid.rotate();
+        grids.push(grid.clone());
+    }
+    grids
+        .into_iter()
+        .unique()
+        .map(|grid| {
+            let cells = grid.find_all(|&cell| cell).collect::<Vec<_>>();
+            let (x, y) = cells.first().copied().unwrap_or_default();
+            cells
+                .iter()
+                .map(|&(cx, cy)| (cx as isize - x as isize, cy as isize - y as isize))
+                .collect()
+        })
+        .collect()
+}
+
+/// The states of a search known not to lead to a packing, from the first undecided cell, the
+/// cells from there on that a present placed at it can reach and the remaining presents to the
+/// largest waste that did not suffice.
+type Failed = HashMap<(usize, Vec<bool>, Vec<usize>), usize>;
+
+/// A search for a packing of presents in the given `orientations`, which are at most `rows` rows
+/// high, giving up after `steps` more states.
+struct Search<'a> {
+    orientations: &'a [Vec<Vec<(isize, isize)>>],
+    rows: usize,
+    failed: Failed,
+    steps: usize,
 }
 
-fn search() {
-    todo!("Nope");
+impl Search<'_> {
+    /// Packs the remaining `presents` of every shape into `region`, leaving at most `waste`
+    /// cells empty, or gives up with `None` once the steps run out.
+    ///
+    /// Every cell before `start` in row-major order is decided already. The first undecided cell
+    /// is either covered by the first cell of a present, or stays empty. Only the cells of the
+    /// next `rows` rows matter for the rest of the search, and states that failed before are not
+    /// searched again.
+    fn pack(
+        &mut self,
+        region: &mut Grid<bool>,
+        start: usize,
+        presents: &mut [usize],
+        waste: usize,
+    ) -> Option<bool> {
+        if presents.iter().all(|&count| count == 0) {
+            return Some(true);
+        }
+        self.steps = self.steps.checked_sub(1)?;
+        let (width, area) = (region.width(), region.width() * region.height());
+        let at = |cell: usize| (cell % width, cell / width);
+        let Some(cell) = (start..area).find(|&cell| !region[at(cell)]) else {
+            return Some(false);
+        };
+        let reach = (cell..area.min(cell + self.rows * width))
+            .map(|cell| region[at(cell)])
+            .collect();
+        let state = (cell, reach, presents.to_vec());
+        if self
+            .failed
+            .get(&state)
+            .is_some_and(|&failed| failed >= waste)
+        {
+            return Some(false);
+        }
+        let pos = at(cell);
+        let orientations = self.orientations;
+        for shape in 0..presents.len() {
+            if presents[shape] == 0 {
+                continue;
+            }
+            for orientation in &orientations[shape] {
+                if cover(region, pos, orientation) {
+                    presents[shape] -= 1;
+                    let found = self.pack(region, cell + 1, presents, waste);
+                    presents[shape] += 1;
+                    uncover(region, pos, orientation);
+                    if found? {
+                        return Some(true);
+                    }
+                }
+            }
+        }
+        if waste > 0 {
+            region[pos] = true;
+            let found = self.pack(region, cell + 1, presents, waste - 1);
+            region[pos] = false;
+            if found? {
+                return Some(true);
+            }
+        }
+        self.failed.insert(state, waste);
+        Some(false)
+    }
+}
+
+fn cells(
+    region: &Grid<bool>,
+    (x, y): (usize, usize),
+    orientation: &[(isize, isize)],
+) -> Option<Vec<(usize, usize)>> {
+    orientation
+        .iter()
+        .map(|&(dx, dy)| {
+            let pos = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+            region.get(pos).is_some_and(|&taken| !taken).then_some(pos)
+        })
+        .collect()
 }
 
-fn cover() {
-    todo!("NOOOPE")
+/// Places a present with its first cell at `pos`, if all its cells are free.
+fn cover(region: &mut Grid<bool>, pos: (usize, usize), orientation: &[(isize, isize)]) -> bool {
+    let Some(cells) = cells(region, pos, orientation) else {
+        return false;
+    };
+    for cell in cells {
+        region[cell] = true;
+    }
+    true
 }
 
-fn uncover() {
-    todo!("EPOOON")
+fn uncover(region: &mut Grid<bool>, (x, y): (usize, usize), orientation: &[(isize, isize)]) {
+    for &(dx, dy) in orientation {
+        region[(x.wrapping_add_signed(dx), y.wrapping_add_signed(dy))] = false;
+    }
+}
+
+/// Whether the presents fit into a region of the given size.
+///
+/// Presents that each get their own 3x3 slot always fit, and presents with more cells than the
+/// region never do. Only the regions in between need a search, which the puzzle inputs never
+/// have. A search that is still undecided after [`SEARCH_STEPS`] steps goes by area alone and
+/// counts the region as fitting.
+fn fits(shapes: &[Grid<bool>], (x, y): (usize, usize), presents: &[usize]) -> bool {
+    if (x / SHAPE_SIZE) * (y / SHAPE_SIZE) >= presents.iter().sum() {
+        return true;
+    }
+    let needed = presents
+        .iter()
+        .zip(shapes)
+        .map(|(&count, shape)| count * shape.find_all(|&cell| cell).count())
+        .sum::<usize>();
+    if needed > x * y || presents.iter().skip(shapes.len()).any(|&count| count > 0) {
+        return false;
+    }
+    let orientations = shapes.iter().map(orientations).collect::<Vec<_>>();
+    // Presents without any cells fit anywhere.
+    let mut presents = presents
+        .iter()
+        .zip(&orientations)
+        .map(|(&count, orientations)| if orientations[0].is_empty() { 0 } else { count })
+        .collect::<Vec<_>>();
+    // Fewer cells per row leave fewer states for the search to tell apart.
+    let mut region = Grid::filled(x.min(y), x.max(y), false);
+    let waste = x * y - needed;
+    let rows = shapes
+        .iter()
+        .map(|shape| shape.width().max(shape.height()))
+        .max()
+        .unwrap_or(0);
+    Search {
+        orientations: &orientations,
+        rows,
+        failed: HashMap::new(),
+        steps: SEARCH_STEPS,
+    }
+    .pack(&mut region, 0, &mut presents, waste)
+    .unwrap_or(true)
 }
 
 const SHAPE_SIZE: usize = 3;
 
+/// The most states a search for a packing of one region visits.
+const SEARCH_STEPS: usize = 50_000;
+
 #[aoc(day12, part1)]
-fn part1(input: &Input) -> usize {
-    let (_, regions) = input;
+pub fn part1(input: &Input) -> usize {
+    part1_observed(input, |_| {})
+}
+
+/// The verdict on one region under the tree.
+pub struct Region<'a> {
+    pub size: (usize, usize),
+    pub presents: &'a [usize],
+    pub fits: bool,
+}
+
+/// Solves part 1, reporting the verdict on every region to `observer`.
+pub fn part1_observed(input: &Input, mut observer: impl FnMut(Region<'_>)) -> usize {
+    let (shapes, regions) = input;
     regions
         .iter()
-        .filter(|((x, y), presents)| (x / SHAPE_SIZE) * (y / SHAPE_SIZE) >= presents.iter().sum())
+        .filter(|&((x, y), presents)| {
+            let fits = fits(shapes, (*x, *y), presents);
+            observer(Region {
+                size: (*x, *y),
+                presents,
+                fits,
+            });
+            fits
+        })
         .count()
-    // Are you kidding me that this works
 }
 
 #[aoc(day12, part2)]
-fn part2(input: &Input) -> usize {
+pub fn part2(_input: &Input) -> usize {
     0
 }
 
+pub struct Day12;
+
+impl Solver for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Input;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -74,8 +287,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "0:
+            part1(
+                &parse(
+                    "0:
 ###
 ##.
 ##.
@@ -109,13 +323,56 @@ mod tests {
 12x5: 1 0 1 0 2 2
 12x5: 1 0 1 0 3 2
 "
-            )),
+                )
+                .unwrap()
+            ),
             2
         );
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("<EXAMPLE>")), 0);
+        assert_eq!(
+            part2(
+                &parse(
+                    "0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2
+"
+                )
+                .unwrap()
+            ),
+            0
+        );
     }
 }
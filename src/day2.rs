@@ -1,82 +1,562 @@
-use anyhow::Result;
-use std::str::FromStr;
+use std::{fmt::Write, ops::RangeInclusive, str::FromStr};
 
+use anyhow::{Result, ensure};
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
+use num::{BigInt, Zero};
 
-#[derive(Debug)]
-struct Id {
-    start: i64,
-    end: i64,
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Id {
+    start: u128,
+    end: u128,
+}
+
+/// Which IDs are invalid: those whose digits in `radix` are a block repeated a number of times
+/// within `min..=max`, or exactly `exact` times if given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    radix: u32,
+    min: u32,
+    max: u32,
+    exact: Option<u32>,
+}
+
+impl Rule {
+    /// Part 1, decimal blocks repeated exactly twice.
+    pub const PART1: Rule = Rule {
+        radix: 10,
+        min: 2,
+        max: 2,
+        exact: Some(2),
+    };
+    /// Part 2, decimal blocks repeated any number of times.
+    pub const PART2: Rule = Rule {
+        radix: 10,
+        min: 2,
+        max: u32::MAX,
+        exact: None,
+    };
+
+    /// Fails for a radix outside 2 to 36 and for a `min` above `max`, which no repetition count
+    /// could satisfy.
+    pub fn new(radix: u32, min: u32, max: u32, exact: Option<u32>) -> Result<Self> {
+        ensure!(
+            (2..=36).contains(&radix),
+            "Radix {radix} is not within 2 to 36."
+        );
+        ensure!(min <= max, "Minimum {min} is above maximum {max}.");
+        Ok(Self {
+            radix,
+            min,
+            max,
+            exact,
+        })
+    }
+
+    /// Whether a block repeated `repetitions` times makes an ID invalid.
+    pub fn accepts(&self, repetitions: u32) -> bool {
+        repetitions > 1
+            && match self.exact {
+                Some(exact) => repetitions == exact,
+                None => (self.min..=self.max).contains(&repetitions),
+            }
+    }
+}
+
+/// An invalid ID together with the block a rule sees repeated in it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Invalid {
+    pub id: u128,
+    pub block: u128,
+    pub repetitions: u32,
+}
+
+/// The invalid IDs of one length whose shortest repeating block has `period` digits.
+#[derive(Clone, Copy)]
+struct Pattern {
+    radix: u128,
+    length: u32,
+    period: u32,
+}
+
+impl Pattern {
+    /// The number that turns a block into its repetitions when multiplied with it, e.g. 10101
+    /// for blocks of 2 digits repeated 3 times, if it fits.
+    fn repeater(&self) -> Option<u128> {
+        let shift = self.radix.pow(self.period);
+        (0..(self.length / self.period))
+            .try_fold(0u128, |acc, _| acc.checked_mul(shift)?.checked_add(1))
+    }
+
+    /// The blocks of `period` digits, shortest or not, whose repetitions lie within `range`.
+    fn blocks(&self, range: &RangeInclusive<u128>) -> RangeInclusive<u128> {
+        // Repetitions too large for IDs lie beyond any range.
+        let Some(repeater) = self.repeater() else {
+            return RangeInclusive::new(1, 0);
+        };
+        let first = range.start().div_ceil(repeater);
+        let last = range.end() / repeater;
+        first.max(self.radix.pow(self.period - 1))..=last.min(self.radix.pow(self.period) - 1)
+    }
+
+    /// Sum and count of all repetitions of blocks of `period` digits within `range`, including
+    /// those whose block repeats itself.
+    fn tally_periodic(&self, range: &RangeInclusive<u128>) -> (BigInt, i128) {
+        let blocks = self.blocks(range);
+        if blocks.is_empty() {
+            return (BigInt::zero(), 0);
+        }
+        let count = blocks.end() - blocks.start() + 1;
+        let repeater = self
+            .repeater()
+            .expect("Blocks exist, so their repetitions fit.");
+        (
+            BigInt::from(repeater) * (BigInt::from(*blocks.start()) + blocks.end()) * count / 2,
+            count as i128,
+        )
+    }
+
+    /// Sum and count of the IDs of this pattern within `range`.
+    ///
+    /// IDs whose shortest block has `period` digits are those with this period minus those with
+    /// a shorter one dividing it, which by inclusion–exclusion, i.e. Möbius inversion over the
+    /// divisors of `period`, counts every ID exactly once.
+    fn tally(&self, range: &RangeInclusive<u128>) -> (BigInt, i128) {
+        divisors(self.period)
+            .map(|period| {
+                let mu = mobius(self.period / period);
+                let (sum, count) = Pattern { period, ..*self }.tally_periodic(range);
+                (sum * mu, count * i128::from(mu))
+            })
+            .fold((BigInt::zero(), 0), |(sum, count), (s, c)| {
+                (sum + s, count + c)
+            })
+    }
+
+    /// The length of the shortest block the `length` digits of `n` are repeated from.
+    fn shortest_period(&self, n: u128) -> u32 {
+        divisors(self.length)
+            .find(|&period| {
+                let block = n % self.radix.pow(period);
+                Pattern { period, ..*self }.repeater() == Some(n / block.max(1)) && block != 0
+            })
+            .unwrap_or(self.length)
+    }
+
+    /// Constructs the IDs of this pattern within `range` in ascending order.
+    fn ids(&self, range: &RangeInclusive<u128>) -> impl Iterator<Item = u128> + use<> {
+        let blocks = self.blocks(range);
+        let repeater = self.repeater().unwrap_or_default();
+        let block = Pattern {
+            length: self.period,
+            ..*self
+        };
+        blocks
+            .filter(move |&n| block.shortest_period(n) == block.period)
+            .map(move |block| block * repeater)
+    }
+}
+
+fn divisors(n: u32) -> impl Iterator<Item = u32> {
+    (1..=n).filter(move |&d| n.is_multiple_of(d))
+}
+
+fn mobius(mut n: u32) -> i32 {
+    let mut mu = 1;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            mu = -mu;
+        }
+        p += 1;
+    }
+    if n > 1 { -mu } else { mu }
+}
+
+fn digits(n: u128, radix: u128) -> u32 {
+    n.checked_ilog(radix).map_or(1, |log| log + 1)
 }
 
 impl Id {
-    fn sum_invalids(&self) -> i64 {
-        (self.start..=self.end)
-            .map(|n| n.to_string())
-            .filter(|n| {
-                let mid = n.len() / 2;
-                n[..mid] == n[mid..]
+    fn range(&self) -> RangeInclusive<u128> {
+        self.start.max(1)..=self.end
+    }
+
+    /// The patterns of the IDs within the range that `rule` considers invalid.
+    ///
+    /// An ID whose shortest block repeats n times also repeats every divisor of n times, e.g.
+    /// 11111111 is 1111 twice.
+    fn patterns(&self, rule: Rule) -> impl Iterator<Item = Pattern> {
+        let range = self.range();
+        let radix = u128::from(rule.radix);
+        let lengths = digits(*range.start(), radix)..=digits(*range.end(), radix);
+        lengths.flat_map(move |length| {
+            divisors(length)
+                .filter(|&period| divisors(length / period).any(|n| rule.accepts(n)))
+                .map(move |period| Pattern {
+                    radix,
+                    length,
+                    period,
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+
+    fn tally(&self, rule: Rule) -> (BigInt, i128) {
+        let range = self.range();
+        self.patterns(rule)
+            .map(|pattern| pattern.tally(&range))
+            .fold((BigInt::zero(), 0), |(sum, count), (s, c)| {
+                (sum + s, count + c)
             })
-            .map(|n| n.parse::<i64>().expect("Was already a number can not fail"))
-            .sum()
     }
 
-    fn sum_invalids2(&self) -> i64 {
-        (self.start..=self.end)
-            .map(|n| n.to_string())
-            .filter(|n| is_repeated(n))
-            .map(|n| n.parse::<i64>().expect("Was already a number can not fail"))
-            .sum()
+    /// The sum of the invalid IDs within the range.
+    pub fn sum(&self, rule: Rule) -> BigInt {
+        self.tally(rule).0
+    }
+
+    /// The number of invalid IDs within the range.
+    pub fn count(&self, rule: Rule) -> u128 {
+        self.tally(rule).1 as u128
+    }
+
+    /// Constructs the invalid IDs in ascending order, without looking at any other number of
+    /// the range.
+    pub fn invalid_ids(&self, rule: Rule) -> impl Iterator<Item = u128> {
+        let range = self.range();
+        self.patterns(rule)
+            .map(|pattern| pattern.ids(&range))
+            .kmerge()
+    }
+
+    /// Explains the invalid IDs in ascending order with the shortest block whose repetitions
+    /// `rule` accepts, e.g. 1111 is 11 twice in part 1 but 1 four times in part 2.
+    pub fn explain(&self, rule: Rule) -> impl Iterator<Item = Invalid> {
+        let range = self.range();
+        self.patterns(rule)
+            .map(|pattern| {
+                let repetitions = divisors(pattern.length / pattern.period)
+                    .filter(|&n| rule.accepts(n))
+                    .max()
+                    .expect("Patterns repeat their block an accepted number of times.");
+                let shift = pattern.radix.pow(pattern.length / repetitions);
+                pattern.ids(&range).map(move |id| Invalid {
+                    id,
+                    block: id % shift,
+                    repetitions,
+                })
+            })
+            .kmerge_by(|a, b| a.id < b.id)
+    }
+
+    /// Parses a range like `a-b` with both ends written in `radix`, which has to be within 2 to
+    /// 36.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        let parser = Parser::new(Day2::DAY, s);
+        if !(2..=36).contains(&radix) {
+            return Err(parser.error(&s[..0], format!("Radix {radix} is not within 2 to 36.")));
+        }
+        let range = parser.range_with(s, |n| u128::from_str_radix(n, radix).ok())?;
+        if range.is_empty() {
+            return Err(parser.error(s, "Range start is after its end."));
+        }
+        Ok(Id {
+            start: *range.start(),
+            end: *range.end(),
+        })
     }
 }
 
-/// A string consists only of the same substring if rotating it yields the same string:
-/// aa => aa
-/// abab => baba => abab
-fn is_repeated(n: &str) -> bool {
-    let mid = n.len() / 2;
-    let n = n.as_bytes();
-    (1..=mid).any(|rot| n == [&n[rot..], &n[..rot]].concat())
+/// Merges overlapping and adjacent ranges, so that IDs within several ranges are only counted
+/// once when summing over the merged ones.
+pub fn merge(input: &[Id]) -> Vec<Id> {
+    let mut ranges = input.to_vec();
+    ranges.sort_by_key(|id| id.start);
+    let mut merged: Vec<Id> = Vec::new();
+    for id in ranges {
+        match merged.last_mut() {
+            Some(last) if id.start <= last.end.saturating_add(1) => last.end = last.end.max(id.end),
+            _ => merged.push(id),
+        }
+    }
+    merged
 }
 
 impl FromStr for Id {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once("-").unwrap();
-        Ok(Id {
-            start: start.parse()?,
-            end: end.parse()?,
-        })
+        Id::from_str_radix(s, 10)
     }
 }
 
 #[aoc_generator(day2)]
-fn parse(input: &str) -> Result<Vec<Id>> {
-    input.split(",").map(Id::from_str).collect()
+pub fn parse(input: &str) -> Result<Vec<Id>, ParseError> {
+    parse_radix(input, 10)
+}
+
+/// Parses comma separated ranges with IDs written in `radix`.
+pub fn parse_radix(input: &str, radix: u32) -> Result<Vec<Id>, ParseError> {
+    input
+        .split(",")
+        .map(|range| Id::from_str_radix(range, radix).map_err(|err| err.relative_to(input, range)))
+        .collect()
+}
+
+/// The sum of all IDs within `input` that `rule` considers invalid.
+pub fn sum_invalid(input: &[Id], rule: Rule) -> BigInt {
+    input.iter().map(|id| id.sum(rule)).sum()
+}
+
+/// The number of IDs within `input` that `rule` considers invalid.
+pub fn count_invalid(input: &[Id], rule: Rule) -> u128 {
+    input.iter().map(|id| id.count(rule)).sum()
+}
+
+fn format_radix(mut n: u128, radix: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit((n % u128::from(radix)) as u32, radix).unwrap());
+        n /= u128::from(radix);
+        if n == 0 {
+            return digits.into_iter().rev().collect();
+        }
+    }
+}
+
+/// Renders the count and sum of the invalid IDs of every range, each followed by its invalid
+/// IDs with their repeated block, the IDs written in the radix of `rule`.
+pub fn render_report(input: &[Id], rule: Rule) -> String {
+    let format = |n| format_radix(n, rule.radix);
+    let mut report = String::new();
+    for id in input {
+        writeln!(
+            report,
+            "{}-{}: {} invalid, sum {}",
+            format(id.start),
+            format(id.end),
+            id.count(rule),
+            id.sum(rule)
+        )
+        .unwrap();
+        for invalid in id.explain(rule) {
+            writeln!(
+                report,
+                "  {} = {} x {}",
+                format(invalid.id),
+                format(invalid.block),
+                invalid.repetitions
+            )
+            .unwrap();
+        }
+    }
+    writeln!(
+        report,
+        "total: {} invalid, sum {}",
+        count_invalid(input, rule),
+        sum_invalid(input, rule)
+    )
+    .unwrap();
+    report
 }
 
 #[aoc(day2, part1)]
-fn part1(input: &[Id]) -> i64 {
-    input.iter().map(|n| n.sum_invalids()).sum()
+pub fn part1(input: &[Id]) -> BigInt {
+    sum_invalid(input, Rule::PART1)
 }
 
 #[aoc(day2, part2)]
-fn part2(input: &[Id]) -> i64 {
-    input.iter().map(|n| n.sum_invalids2()).sum()
+pub fn part2(input: &[Id]) -> BigInt {
+    sum_invalid(input, Rule::PART2)
+}
+
+pub struct Day2;
+
+impl Solver for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<Id>;
+    type Part1 = BigInt;
+    type Part2 = BigInt;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// A string consists only of the same substring if rotating it yields the same string:
+    /// aa => aa
+    /// abab => baba => abab
+    fn is_repeated(n: &str) -> bool {
+        let mid = n.len() / 2;
+        let n = n.as_bytes();
+        (1..=mid).any(|rot| n == [&n[rot..], &n[..rot]].concat())
+    }
+
+    #[test]
+    fn enumeration_matches_scan() {
+        for (start, end) in [
+            (1, 20_000),
+            (95, 115),
+            (999_990, 1_011_000),
+            (1_111_111, 1_111_111),
+        ] {
+            let id = Id { start, end };
+            let scan = (start..=end)
+                .map(|n| n.to_string())
+                .filter(|n| is_repeated(n))
+                .map(|n| n.parse::<u128>().unwrap())
+                .collect::<Vec<_>>();
+            let mut ids = id.invalid_ids(Rule::PART2).collect::<Vec<_>>();
+            ids.sort();
+            assert_eq!(ids, scan);
+            assert_eq!(id.sum(Rule::PART2), BigInt::from(scan.iter().sum::<u128>()));
+            assert_eq!(id.count(Rule::PART2), scan.len() as u128);
+            let halves = scan
+                .iter()
+                .map(|n| n.to_string())
+                .filter(|n| n[..(n.len() / 2)] == n[(n.len() / 2)..])
+                .map(|n| n.parse::<u128>().unwrap())
+                .sum::<u128>();
+            assert_eq!(id.sum(Rule::PART1), BigInt::from(halves));
+        }
+    }
+
+    #[test]
+    fn wide_ranges() {
+        let id = Id {
+            start: 1,
+            end: 999_999_999_999,
+        };
+        // Every block of 1 to 6 digits repeated twice.
+        let twice = (1..=6)
+            .map(|digits| {
+                let blocks = 10u128.pow(digits - 1)..10u128.pow(digits);
+                blocks.sum::<u128>() * (10u128.pow(digits) + 1)
+            })
+            .sum::<u128>();
+        assert_eq!(id.sum(Rule::PART1), BigInt::from(twice));
+
+        // Sums of IDs with up to 39 digits no longer fit any primitive integer.
+        let id = Id {
+            start: 1,
+            end: u128::MAX,
+        };
+        assert!(id.sum(Rule::PART2) > BigInt::from(u128::MAX));
+        // 39 digits are an odd number, so none of them is a block repeated twice.
+        let id = Id {
+            start: 10u128.pow(38) - 10,
+            end: u128::MAX,
+        };
+        assert_eq!(
+            id.invalid_ids(Rule::PART1).collect::<Vec<_>>(),
+            [10u128.pow(38) - 1]
+        );
+    }
+
+    #[test]
+    fn explains_invalid_ids() {
+        let id = "1100-1212".parse::<Id>().unwrap();
+        let explain = |rule| {
+            id.explain(rule)
+                .map(|invalid| (invalid.id, invalid.block, invalid.repetitions))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(explain(Rule::PART1), [(1111, 11, 2), (1212, 12, 2)]);
+        assert_eq!(explain(Rule::PART2), [(1111, 1, 4), (1212, 12, 2)]);
+
+        let report = render_report(
+            &parse_radix("a0-ff", 16).unwrap(),
+            Rule::new(16, 2, 2, None).unwrap(),
+        );
+        assert!(report.starts_with("a0-ff: 6 invalid, sum 1275\n  aa = a x 2\n"));
+        assert!(report.ends_with("  ff = f x 2\ntotal: 6 invalid, sum 1275\n"));
+    }
+
+    #[test]
+    fn validates_and_merges_ranges() {
+        let err = parse("11-22,30-25").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (7, "30-25"));
+        let err = parse("11-22,30").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (9, ""));
+        for radix in [1, 37] {
+            let err = parse_radix("11-22,30-35", radix).unwrap_err();
+            assert_eq!((err.line, err.column, err.text.as_str()), (1, 1, ""));
+        }
+
+        let ids = parse("95-115,11-22,100-120,121-130,1000-1001").unwrap();
+        let merged = merge(&ids);
+        assert_eq!(merged, parse("11-22,95-130,1000-1001").unwrap());
+        // 111 lies within two of the ranges.
+        assert_eq!(
+            sum_invalid(&ids, Rule::PART2) - sum_invalid(&merged, Rule::PART2),
+            BigInt::from(111)
+        );
+    }
+
+    #[test]
+    fn configurable_rules() {
+        let tags = parse_radix("a0-ff,abab-abac,fff-1001", 16).unwrap();
+        // aa, bb, cc, dd, ee and ff, abab, but fff and 1001 are no pairs.
+        let hex = Rule::new(16, 2, 2, None).unwrap();
+        assert_eq!(count_invalid(&tags, hex), 7);
+        assert_eq!(
+            sum_invalid(&tags, hex),
+            BigInt::from(0x11 * (0xa + 0xb + 0xc + 0xd + 0xe + 0xf) + 0xabab)
+        );
+        // fff is f repeated three times.
+        assert_eq!(count_invalid(&tags, Rule::new(16, 3, 8, None).unwrap()), 1);
+
+        let ids = parse("1-1000000").unwrap();
+        // Blocks of 1 or 2 digits repeated exactly three times.
+        assert_eq!(
+            count_invalid(&ids, Rule::new(10, 2, 9, Some(3)).unwrap()),
+            9 + 90
+        );
+        // IDs have 6 digits at most, so 4 to 6 repetitions only allow single digits.
+        assert_eq!(
+            count_invalid(&ids, Rule::new(10, 4, 6, None).unwrap()),
+            9 * 3
+        );
+        // 10 is 1010 and 15 is 1111 in binary.
+        let binary = parse_radix("1010-1111", 2).unwrap();
+        assert_eq!(
+            sum_invalid(&binary, Rule::new(2, 2, 4, None).unwrap()),
+            BigInt::from(10 + 15)
+        );
+
+        // No count of repetitions is within 4 to 3, and no radix is 1 or 37.
+        assert!(Rule::new(10, 4, 3, None).is_err());
+        assert!(Rule::new(1, 2, 2, None).is_err());
+        assert!(Rule::new(37, 2, 2, None).is_err());
+    }
+
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse("11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124").unwrap()), 1227775554);
+        assert_eq!(part1(&parse("11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124").unwrap()), BigInt::from(1227775554));
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse("11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124").unwrap()), 4174379265);
+        assert_eq!(part2(&parse("11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124").unwrap()), BigInt::from(4174379265u64));
     }
 }
@@ -1,80 +1,480 @@
+use std::collections::VecDeque;
+
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
+use num::{BigInt, Zero};
+
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
 #[aoc_generator(day3)]
-fn parse(input: &str) -> Vec<Vec<i64>> {
+pub fn parse(input: &str) -> Result<Vec<Vec<i64>>, ParseError> {
+    parse_radix(input, 10)
+}
+
+/// Parses one bank per line with joltages below `radix`.
+///
+/// A bank is either written as one digit per battery, or as comma separated decimal values.
+/// Blank lines and comments starting with `#` only separate banks. Radixes outside of 2 to 36
+/// are reported at the start of the input.
+pub fn parse_radix(input: &str, radix: u32) -> Result<Vec<Vec<i64>>, ParseError> {
+    let parser = Parser::new(Day3::DAY, input);
+    if !(2..=36).contains(&radix) {
+        return Err(parser.error(&input[..0], format!("Radix {radix} is not within 2 to 36.")));
+    }
     input
         .lines()
-        .map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).expect("Unexpected character in input.") as i64)
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|bank| !bank.is_empty())
+        .map(|bank| {
+            if !bank.contains(',') {
+                let digits = parser.digits(bank, radix)?;
+                return Ok(digits.into_iter().map(i64::from).collect());
+            }
+            bank.split(',')
+                .map(|token| {
+                    let token = token.trim();
+                    let joltage = parser.number(token, "Joltage is not a number.")?;
+                    if !(0..i64::from(radix)).contains(&joltage) {
+                        return Err(
+                            parser.error(token, format!("Joltage is not from 0 to {}.", radix - 1))
+                        );
+                    }
+                    Ok(joltage)
+                })
                 .collect()
         })
         .collect()
 }
 
-fn find_biggest_n_digit_number(bank: &[i64], digits: usize) -> i64 {
-    let mut current_pos = 0;
-    (0..digits)
-        .rev()
-        .map(|digit| {
-            let max_n = bank[current_pos..(bank.len() - digit)]
-                .iter()
-                .max()
-                .expect("For the numbers, there will always be a biggest number.");
-            current_pos += bank[current_pos..]
-                .iter()
-                .position(|battery| battery == max_n)
-                .expect("Maximum value not in numbers.")
-                + 1;
-            *max_n
-        })
-        .fold(0, |acc, max_n| acc * 10 + max_n)
+/// Batteries turned on in a bank, in the order they appear in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub indices: Vec<usize>,
+    pub digits: Vec<i64>,
 }
 
-#[aoc(day3, part1)]
-fn part1(input: &[Vec<i64>]) -> i64 {
+impl Selection {
+    /// The joltage of the selected batteries read as one number in `radix`.
+    pub fn joltage(&self, radix: u32) -> BigInt {
+        self.digits
+            .iter()
+            .fold(BigInt::zero(), |acc, &digit| acc * radix + digit)
+    }
+
+    /// Writes the joltage in `radix`, with comma separated decimal values for radixes beyond 36.
+    pub fn render(&self, radix: u32) -> String {
+        if radix > 36 {
+            return self.digits.iter().join(",");
+        }
+        self.digits
+            .iter()
+            .map(|&digit| {
+                char::from_digit(digit as u32, radix).expect("Joltages are below the radix.")
+            })
+            .collect()
+    }
+}
+
+impl Selection {
+    fn new(bank: &[i64], indices: Vec<usize>) -> Self {
+        Selection {
+            digits: indices.iter().map(|&pos| bank[pos]).collect(),
+            indices,
+        }
+    }
+}
+
+/// Keeps `k` of the digits of `bank`, dropping the top of the stack of kept ones whenever
+/// `drops(top, digit)` holds for the next digit while there are still batteries to spare.
+///
+/// Every battery is pushed and popped at most once, which takes O(n) for n batteries whatever
+/// `k` is.
+fn monotonic(bank: &[i64], k: usize, drops: impl Fn(i64, i64) -> bool) -> Vec<usize> {
+    let mut spare = bank.len() - k;
+    let mut stack = Vec::with_capacity(bank.len());
+    for (pos, &digit) in bank.iter().enumerate() {
+        while spare > 0 && stack.last().is_some_and(|&top| drops(bank[top], digit)) {
+            stack.pop();
+            spare -= 1;
+        }
+        stack.push(pos);
+    }
+    stack.truncate(k);
+    stack
+}
+
+/// Selects the `k` batteries of `bank` whose digits form the largest number, or `None` if the
+/// bank has fewer than `k` batteries.
+///
+/// A digit is dropped whenever a larger one follows it, so the kept digits never increase from
+/// the first to the last.
+pub fn largest(bank: &[i64], k: usize) -> Option<Selection> {
+    if k > bank.len() {
+        return None;
+    }
+    Some(Selection::new(
+        bank,
+        monotonic(bank, k, |top, digit| top < digit),
+    ))
+}
+
+/// Which `k` batteries of a bank to turn on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// The largest number, as in both parts.
+    Largest,
+    /// The smallest number that does not start with a zero.
+    Smallest,
+    /// The largest number from batteries at least this many positions apart.
+    Spaced(usize),
+    /// The largest number whose digits sum to at most this budget.
+    Budget(i64),
+}
+
+/// Selects `k` batteries of `bank` as `mode` asks for, or `None` if no `k` batteries meet its
+/// constraint.
+pub fn select(bank: &[i64], k: usize, mode: Mode) -> Option<Selection> {
+    if k > bank.len() {
+        return None;
+    }
+    match mode {
+        Mode::Largest => largest(bank, k),
+        Mode::Smallest => smallest(bank, k),
+        Mode::Spaced(gap) => spaced(bank, k, gap.max(1)),
+        Mode::Budget(budget) => budgeted(bank, k, budget),
+    }
+}
+
+/// The first digit is the leftmost smallest one other than zero that leaves enough batteries,
+/// and the rest the smallest number behind it, found like [`largest`] with the order reversed.
+fn smallest(bank: &[i64], k: usize) -> Option<Selection> {
+    if k == 0 {
+        return Some(Selection::new(bank, Vec::new()));
+    }
+    let first = (0..=(bank.len() - k))
+        .filter(|&pos| bank[pos] != 0)
+        .min_by_key(|&pos| bank[pos])?;
+    let rest = monotonic(&bank[(first + 1)..], k - 1, |top, digit| top > digit);
+    let indices = std::iter::once(first)
+        .chain(rest.into_iter().map(|pos| first + 1 + pos))
+        .collect();
+    Some(Selection::new(bank, indices))
+}
+
+/// Picks the leftmost largest digit that leaves room for the remaining batteries, one digit at a
+/// time.
+///
+/// The window of candidates for the next digit starts `gap` behind the previous one and ends
+/// `gap` further than for the previous one, so a queue of candidates with decreasing digits
+/// finds the largest in O(n) overall.
+fn spaced(bank: &[i64], k: usize, gap: usize) -> Option<Selection> {
+    if k == 0 {
+        return Some(Selection::new(bank, Vec::new()));
+    }
+    if (k - 1) * gap >= bank.len() {
+        return None;
+    }
+    let mut candidates = VecDeque::new();
+    let (mut start, mut next) = (0, 0);
+    let mut indices = Vec::with_capacity(k);
+    for rest in (0..k).rev() {
+        let end = bank.len() - 1 - rest * gap;
+        while next <= end {
+            while candidates
+                .back()
+                .is_some_and(|&last| bank[last] < bank[next])
+            {
+                candidates.pop_back();
+            }
+            candidates.push_back(next);
+            next += 1;
+        }
+        while candidates.front().is_some_and(|&first| first < start) {
+            candidates.pop_front();
+        }
+        let pos = candidates.pop_front().expect("The window is never empty.");
+        indices.push(pos);
+        start = pos + gap;
+    }
+    Some(Selection::new(bank, indices))
+}
+
+/// Picks the largest digit whose leftmost occurrence still leaves enough batteries to complete
+/// the number within the budget, one digit at a time.
+///
+/// Tables of the next occurrence of every digit and of the digits left behind every position
+/// make each choice take O(r²) for digits below r, whatever the size of the bank.
+fn budgeted(bank: &[i64], k: usize, budget: i64) -> Option<Selection> {
+    let radix = bank.iter().max().map_or(1, |&digit| digit as usize + 1);
+    // next[pos][digit]: the first position from pos on holding the digit.
+    // behind[pos][digit]: how many batteries from pos on hold the digit.
+    let mut next = vec![vec![None; radix]; bank.len() + 1];
+    let mut behind = vec![vec![0; radix]; bank.len() + 1];
+    for pos in (0..bank.len()).rev() {
+        next[pos] = next[pos + 1].clone();
+        next[pos][bank[pos] as usize] = Some(pos);
+        behind[pos] = behind[pos + 1].clone();
+        behind[pos][bank[pos] as usize] += 1;
+    }
+    // The smallest sum of `count` digits from pos on.
+    let cheapest = |pos: usize, mut count: usize| {
+        let mut sum = 0;
+        for (digit, &available) in behind[pos].iter().enumerate() {
+            let taken = count.min(available);
+            sum += digit as i64 * taken as i64;
+            count -= taken;
+        }
+        sum
+    };
+    if cheapest(0, k) > budget {
+        return None;
+    }
+    let (mut start, mut left) = (0, budget);
+    let mut indices = Vec::with_capacity(k);
+    for rest in (0..k).rev() {
+        let pos = (0..radix)
+            .rev()
+            .filter_map(|digit| next[start][digit])
+            .find(|&pos| pos + rest < bank.len() && bank[pos] + cheapest(pos + 1, rest) <= left)
+            .expect("A digit that fits the budget is left.");
+        indices.push(pos);
+        left -= bank[pos];
+        start = pos + 1;
+    }
+    Some(Selection::new(bank, indices))
+}
+
+/// Banks with fewer than `k` batteries cannot turn on `k` of them and add nothing.
+fn total_joltage(input: &[Vec<i64>], k: usize) -> BigInt {
     input
         .iter()
-        .map(|bank| find_biggest_n_digit_number(bank, 2))
+        .filter_map(|bank| largest(bank, k))
+        .map(|selection| selection.joltage(10))
         .sum()
 }
 
+#[aoc(day3, part1)]
+pub fn part1(input: &[Vec<i64>]) -> BigInt {
+    total_joltage(input, 2)
+}
+
 #[aoc(day3, part2)]
-fn part2(input: &[Vec<i64>]) -> i64 {
-    input
-        .iter()
-        .map(|bank| find_biggest_n_digit_number(bank, 12))
-        .sum()
+pub fn part2(input: &[Vec<i64>]) -> BigInt {
+    total_joltage(input, 12)
+}
+
+pub struct Day3;
+
+impl Solver for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Vec<i64>>;
+    type Part1 = BigInt;
+    type Part2 = BigInt;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::Rng;
+
+    /// Picks the largest digit that leaves enough batteries for the rest, one digit at a time.
+    fn greedy(bank: &[i64], k: usize) -> Vec<usize> {
+        let mut start = 0;
+        (0..k)
+            .rev()
+            .map(|rest| {
+                let window = &bank[start..(bank.len() - rest)];
+                let max = window.iter().max().unwrap();
+                let pos = start + window.iter().position(|digit| digit == max).unwrap();
+                start = pos + 1;
+                pos
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stack_matches_greedy() {
+        let mut rng = Rng::new(3);
+        for (batteries, k) in [
+            (15, 2),
+            (15, 12),
+            (15, 15),
+            (2000, 1),
+            (3000, 250),
+            (3000, 2999),
+        ] {
+            let bank = (0..batteries)
+                .map(|_| rng.range(1..=9) as i64)
+                .collect::<Vec<_>>();
+            let selection = largest(&bank, k).unwrap();
+            assert_eq!(selection.indices, greedy(&bank, k));
+            assert!(
+                selection
+                    .indices
+                    .iter()
+                    .zip(&selection.digits)
+                    .all(|(&pos, &digit)| bank[pos] == digit)
+            );
+            assert_eq!(selection.joltage(10).to_string(), selection.render(10));
+        }
+    }
+
+    #[test]
+    fn modes_match_exhaustive_search() {
+        let mut rng = Rng::new(21);
+        for _ in 0..200 {
+            let bank = (0..rng.range(1..=9))
+                .map(|_| rng.range(0..=9) as i64)
+                .collect::<Vec<_>>();
+            let k = rng.below(bank.len() + 1);
+            let subsets = (0..(1 << bank.len()))
+                .filter(|subset: &u32| subset.count_ones() as usize == k)
+                .map(|subset| {
+                    let indices = (0..bank.len())
+                        .filter(|pos| subset & (1 << pos) != 0)
+                        .collect::<Vec<_>>();
+                    Selection::new(&bank, indices)
+                })
+                .collect::<Vec<_>>();
+            let best = |mode, valid: &dyn Fn(&Selection) -> bool| {
+                let valid = subsets.iter().filter(|selection| valid(selection));
+                let best = if mode == Mode::Smallest {
+                    valid.min_by_key(|selection| &selection.digits)
+                } else {
+                    valid.max_by_key(|selection| &selection.digits)
+                };
+                best.map(|selection| selection.digits.clone())
+            };
+            let gap = rng.range(1..=3) as usize;
+            let budget = rng.range(0..=30) as i64;
+            for (mode, valid) in [
+                (
+                    Mode::Largest,
+                    &(|_: &Selection| true) as &dyn Fn(&Selection) -> bool,
+                ),
+                (Mode::Smallest, &|selection| {
+                    selection.digits.first() != Some(&0)
+                }),
+                (Mode::Spaced(gap), &|selection| {
+                    selection
+                        .indices
+                        .windows(2)
+                        .all(|pair| pair[1] - pair[0] >= gap)
+                }),
+                (Mode::Budget(budget), &|selection| {
+                    selection.digits.iter().sum::<i64>() <= budget
+                }),
+            ] {
+                let selection = select(&bank, k, mode);
+                assert!(
+                    selection.as_ref().is_none_or(valid),
+                    "{bank:?} {k} {mode:?}"
+                );
+                assert_eq!(
+                    selection.map(|selection| selection.digits),
+                    best(mode, valid),
+                    "{bank:?} {k} {mode:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn selects_indices_and_digits() {
+        let selection = largest(&[8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1], 12).unwrap();
+        assert_eq!(selection.render(10), "888911112111");
+        assert_eq!(selection.indices, [0, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(largest(&[5, 5, 5], 0).unwrap().joltage(10), BigInt::from(0));
+        assert_eq!(largest(&[5, 5, 5], 4), None);
+
+        let bank = [0, 3, 0, 9, 1, 0, 2];
+        let select = |mode| select(&bank, 3, mode).map(|selection| selection.indices);
+        assert_eq!(select(Mode::Smallest), Some(vec![4, 5, 6]));
+        assert_eq!(select(Mode::Spaced(2)), Some(vec![1, 3, 6]));
+        assert_eq!(select(Mode::Spaced(4)), None);
+        assert_eq!(select(Mode::Budget(6)), Some(vec![1, 4, 6]));
+        assert_eq!(select(Mode::Budget(0)), Some(vec![0, 2, 5]));
+    }
+
+    #[test]
+    fn parses_other_radixes() {
+        let input = "# hex banks\n3fa9\n\n0c1 # trailing\n12, 30,7,0\n";
+        let banks = parse_radix(input, 36).unwrap();
+        assert_eq!(banks[..2], [vec![3, 15, 10, 9], vec![0, 12, 1]]);
+        assert_eq!(banks[2], [12, 30, 7, 0]);
+
+        let selection = largest(&banks[0], 2).unwrap();
+        assert_eq!(selection.render(16), "fa");
+        assert_eq!(selection.joltage(16), BigInt::from(0xfa));
+        let selection = largest(&banks[2], 2).unwrap();
+        assert_eq!(selection.render(36), "u7");
+        assert_eq!(selection.render(40), "30,7");
+        assert_eq!(selection.joltage(36), BigInt::from(30 * 36 + 7));
+
+        let err = parse_radix("12\n3g4", 16).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "g"));
+        let err = parse_radix("1,2\n3, 16", 16).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "16"));
+        let err = parse_radix("1,-2", 16).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 3, "-2"));
+        for radix in [0, 1, 37] {
+            let err = parse_radix("12", radix).unwrap_err();
+            assert_eq!((err.line, err.column, err.text.as_str()), (1, 1, ""));
+        }
+    }
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "987654321111111
+            part1(
+                &parse(
+                    "987654321111111
 811111111111119
 234234234234278
 818181911112111
 "
-            )),
-            357
+                )
+                .unwrap()
+            ),
+            BigInt::from(357)
+        );
+    }
+
+    #[test]
+    fn part2_skips_short_banks() {
+        assert_eq!(
+            part2(&parse("987654321\n987654321111111").unwrap()),
+            BigInt::from(987654321111u64)
         );
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "987654321111111
+            part2(
+                &parse(
+                    "987654321111111
 811111111111119
 234234234234278
 818181911112111
 "
-            )),
-            3121910778619
+                )
+                .unwrap()
+            ),
+            BigInt::from(3121910778619u64)
         );
     }
 }
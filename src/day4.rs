@@ -1,60 +1,216 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use pathfinding::grid::Grid;
+
+use crate::{
+    error::ParseError,
+    solver::Solver,
+    utils::{
+        automaton::{Automaton, Counts, Neighbourhood, Rule},
+        grid::Grid,
+    },
+};
 
 #[aoc_generator(day4)]
-fn parse(input: &str) -> Grid {
-    let mut grid = Grid::from_coordinates(
-        &input
-            .lines()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.chars()
-                    .enumerate()
-                    .filter(|(_, c)| *c == '@')
-                    .map(move |(x, _)| (x, y))
-            })
-            .collect::<Vec<_>>(),
-    )
-    .unwrap();
-
-    grid.enable_diagonal_mode();
-    grid
+pub fn parse(input: &str) -> Result<Grid<bool>, ParseError> {
+    Grid::parse(Day4::DAY, input, |c| match c {
+        '@' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+}
+
+/// Forklifts can reach rolls with fewer than 4 rolls around them, which removes them.
+pub fn forklifts() -> Automaton {
+    Automaton::new(Neighbourhood::Moore, Rule::removing(Counts::below(4)))
 }
 
 #[aoc(day4, part1)]
-fn part1(input: &Grid) -> usize {
-    input
-        .iter()
-        .filter(|roll| input.neighbours(*roll).len() < 4)
-        .count()
+pub fn part1(input: &Grid<bool>) -> usize {
+    forklifts().changes(input).len()
 }
 
 #[aoc(day4, part2)]
-fn part2(input: &Grid) -> usize {
+pub fn part2(input: &Grid<bool>) -> usize {
+    part2_observed(input, |_| {})
+}
+
+/// One round of removals, showing the rolls before the accessible ones are removed.
+pub struct Round<'a> {
+    pub number: usize,
+    pub grid: &'a Grid<bool>,
+    pub removed: &'a [(usize, usize)],
+}
+
+/// Solves part 2, reporting every round of removals to `observer`.
+///
+/// Like a k-core decomposition, this keeps the number of rolls around every roll and only
+/// updates the neighbours of removed rolls. A roll joins the next round when its count drops
+/// below 4, so every roll is removed at most once and the grid is never scanned again, which
+/// takes linear time in the size of the grid.
+pub fn part2_observed(input: &Grid<bool>, mut observer: impl FnMut(Round<'_>)) -> usize {
     let mut grid = input.clone();
+    let mut neighbours = grid.map(|_| 0u8);
+    for roll in grid.find_all(|&roll| roll) {
+        neighbours[roll] = grid
+            .neighbours8(roll)
+            .filter(|&neighbour| grid[neighbour])
+            .count() as u8;
+    }
+    let mut rolls = grid
+        .find_all(|&roll| roll)
+        .filter(|&roll| neighbours[roll] < 4)
+        .collect::<Vec<_>>();
     let mut removed = 0;
-    while grid.iter().any(|roll| grid.neighbours(roll).len() < 4) {
-        let old_grid = grid.clone();
-        old_grid
-            .iter()
-            .filter(|roll| old_grid.neighbours(*roll).len() < 4)
-            .for_each(|roll| {
-                grid.remove_vertex(roll);
-            });
-        removed += old_grid.vertices_len() - grid.vertices_len();
+    for number in 1.. {
+        if rolls.is_empty() {
+            break;
+        }
+        observer(Round {
+            number,
+            grid: &grid,
+            removed: &rolls,
+        });
+        removed += rolls.len();
+        for &roll in &rolls {
+            grid[roll] = false;
+        }
+        let mut next = Vec::new();
+        for roll in rolls {
+            for neighbour in grid.neighbours8(roll) {
+                if grid[neighbour] {
+                    neighbours[neighbour] -= 1;
+                    if neighbours[neighbour] == 3 {
+                        next.push(neighbour);
+                    }
+                }
+            }
+        }
+        rolls = next;
     }
     removed
 }
 
+/// The round in which every roll is removed, with `None` for the rolls that are never removed
+/// and for empty cells.
+pub fn removal_rounds(input: &Grid<bool>) -> Grid<Option<usize>> {
+    let mut rounds = input.map(|_| None);
+    part2_observed(input, |round| {
+        for &roll in round.removed {
+            rounds[roll] = Some(round.number);
+        }
+    });
+    rounds
+}
+
+/// The number of rolls removed in every round, starting with the first.
+pub fn histogram(rounds: &Grid<Option<usize>>) -> Vec<usize> {
+    let mut histogram = Vec::new();
+    for &round in rounds.iter().filter_map(|(_, round)| round.as_ref()) {
+        if histogram.len() < round {
+            histogram.resize(round, 0);
+        }
+        histogram[round - 1] += 1;
+    }
+    histogram
+}
+
+/// Draws the rolls with the round they are removed in, as `1` to `9` and then `a` to `z`, or `+`
+/// for later rounds. Rolls that are never removed stay `@`.
+pub fn render_rounds(input: &Grid<bool>, rounds: &Grid<Option<usize>>) -> String {
+    let glyphs = input
+        .iter()
+        .map(|(pos, &roll)| match (roll, rounds[pos]) {
+            (false, _) => '.',
+            (true, None) => '@',
+            (true, Some(round)) => u32::try_from(round)
+                .ok()
+                .and_then(|round| char::from_digit(round, 36))
+                .unwrap_or('+'),
+        })
+        .collect();
+    Grid::new(input.width(), input.height(), glyphs).render(|&glyph| glyph)
+}
+
+pub struct Day4;
+
+impl Solver for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Grid<bool>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        generate::{self, Rng},
+        utils::automaton::Outcome,
+    };
+
+    #[test]
+    fn peeling_matches_automaton() {
+        let rng = &mut Rng::new(4);
+        for density in [30, 60, 80] {
+            let grid = parse(&generate::day4(rng, 60, 40, density)).unwrap();
+            let mut rounds = Vec::new();
+            let removed = part2_observed(&grid, |round| {
+                let mut removed = round.removed.to_vec();
+                removed.sort_by_key(|&(x, y)| (y, x));
+                rounds.push(removed);
+            });
+            let mut generations = Vec::new();
+            let (left, outcome) = forklifts().run(&grid, |generation| {
+                generations.push(generation.changed.to_vec());
+            });
+            assert!(matches!(outcome, Outcome::Fixed { .. }));
+            assert_eq!(rounds, generations);
+            assert_eq!(
+                removed,
+                grid.find_all(|&roll| roll).count() - left.find_all(|&roll| roll).count()
+            );
+        }
+    }
+
+    #[test]
+    fn maps_removal_rounds() {
+        // The octagon is a stable core, every roll of it has 4 rolls around it.
+        let grid = parse(".@@...@@@\n@@@@..@@@\n@@@@.....\n.@@......").unwrap();
+        let rounds = removal_rounds(&grid);
+        assert_eq!(
+            render_rounds(&grid, &rounds),
+            ".@@...121\n@@@@..121\n@@@@.....\n.@@......"
+        );
+        assert_eq!(histogram(&rounds), [4, 2]);
+        assert_eq!(histogram(&rounds).iter().sum::<usize>(), part2(&grid));
+        assert_eq!(rounds[(1, 1)], None);
+        assert_eq!(rounds[(0, 0)], None);
+    }
+
+    #[test]
+    fn peels_large_warehouses() {
+        let grid = parse(&generate::day4(&mut Rng::new(24), 1000, 1000, 70)).unwrap();
+        assert!(part2(&grid) > part1(&grid));
+    }
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "..@@.@@@@.
+            part1(
+                &parse(
+                    "..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
 @.@@@@..@.
@@ -65,7 +221,9 @@ mod tests {
 .@@@@@@@@.
 @.@.@@@.@.
 "
-            )),
+                )
+                .unwrap()
+            ),
             13
         );
     }
@@ -73,8 +231,9 @@ mod tests {
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "..@@.@@@@.
+            part2(
+                &parse(
+                    "..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
 @.@@@@..@.
@@ -85,7 +244,9 @@ mod tests {
 .@@@@@@@@.
 @.@.@@@.@.
 "
-            )),
+                )
+                .unwrap()
+            ),
             43
         );
     }
@@ -1,4 +1,3 @@
-use anyhow::Result;
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 use std::{
@@ -6,33 +5,26 @@ use std::{
     ops::RangeInclusive,
 };
 
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
 #[aoc_generator(day5)]
-fn parse(input: &str) -> (Vec<RangeInclusive<i64>>, Vec<i64>) {
-    let (fresh_ingredients, available_ingredients) = input
-        .split_once("\n\n")
-        .expect("Empty line missing in input.");
+pub fn parse(input: &str) -> Result<(Vec<RangeInclusive<i64>>, Vec<i64>), ParseError> {
+    let parser = Parser::new(Day5::DAY, input);
+    let (fresh_ingredients, available_ingredients) =
+        parser.split(input, "\n\n", "Empty line missing in input.")?;
     let fresh_ingredients = fresh_ingredients
         .lines()
-        .map(|line| {
-            let (start, end) = line
-                .split_once("-")
-                .expect("Fresh ingredient line without -.");
-            (start.parse::<i64>().expect("Range start is not a number."))
-                ..=(end.parse::<i64>().expect("Range end is not a number."))
-        })
-        .collect::<Vec<_>>();
+        .map(|line| parser.range(line))
+        .collect::<Result<Vec<_>, _>>()?;
     let available_ingredients = available_ingredients
         .lines()
-        .map(|line| {
-            line.parse::<i64>()
-                .expect("Not a ingredient is not a number.")
-        })
-        .collect::<Vec<_>>();
-    (fresh_ingredients, available_ingredients)
+        .map(|line| parser.number(line, "Ingredient is not a number."))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((fresh_ingredients, available_ingredients))
 }
 
 #[aoc(day5, part1)]
-fn part1(input: &(Vec<RangeInclusive<i64>>, Vec<i64>)) -> usize {
+pub fn part1(input: &(Vec<RangeInclusive<i64>>, Vec<i64>)) -> usize {
     let (fresh_ingredients, available_ingredients) = input;
     available_ingredients
         .iter()
@@ -93,7 +85,7 @@ impl Ord for Range {
 }
 
 #[aoc(day5, part2)]
-fn part2(input: &(Vec<RangeInclusive<i64>>, Vec<i64>)) -> usize {
+pub fn part2(input: &(Vec<RangeInclusive<i64>>, Vec<i64>)) -> usize {
     let (fresh_ingredients, _) = input;
     let fresh_ingredients = fresh_ingredients
         .iter()
@@ -120,6 +112,28 @@ fn part2(input: &(Vec<RangeInclusive<i64>>, Vec<i64>)) -> usize {
     unique_ingredients.iter().map(|range| range.len()).sum()
 }
 
+pub struct Day5;
+
+impl Solver for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = (Vec<RangeInclusive<i64>>, Vec<i64>);
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,8 +141,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "3-5
+            part1(
+                &parse(
+                    "3-5
 10-14
 16-20
 12-18
@@ -139,7 +154,9 @@ mod tests {
 11
 17
 32"
-            )),
+                )
+                .unwrap()
+            ),
             3
         );
     }
@@ -147,8 +164,9 @@ mod tests {
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "3-5
+            part2(
+                &parse(
+                    "3-5
 10-14
 16-20
 12-18
@@ -159,8 +177,21 @@ mod tests {
 11
 17
 32"
-            )),
+                )
+                .unwrap()
+            ),
             14
         );
     }
+
+    #[test]
+    fn parse_trailing_newline() {
+        assert_eq!(parse("3-5\n\n1\n4\n"), Ok((vec![3..=5], vec![1, 4])));
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = parse("3-5\n10-x4\n\n1").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "x4"));
+    }
 }
@@ -1,5 +1,11 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::{
+    error::ParseError,
+    solver::Solver,
+    utils::{grid::Grid, parse::Parser},
+};
+
 #[derive(Debug)]
 struct Problem {
     numbers: Vec<i64>,
@@ -20,12 +26,37 @@ impl Problem {
 }
 
 #[aoc_generator(day6)]
-fn parse(input: &str) -> String {
-    input.to_owned()
+pub fn parse(input: &str) -> Result<String, ParseError> {
+    let parser = Parser::new(Day6::DAY, input);
+    let mut lines = input.lines();
+    let symbols = lines
+        .next_back()
+        .ok_or_else(|| parser.error(input, "Empty worksheet."))?;
+    if lines.clone().next().is_none() {
+        return Err(parser.error(symbols, "Worksheet without numbers."));
+    }
+    let unexpected = |line: &str, pos: usize, c: char, reason: &str| {
+        parser.error(&line[pos..(pos + c.len_utf8())], reason)
+    };
+    for line in lines {
+        if let Some((pos, c)) = line
+            .char_indices()
+            .find(|(_, c)| !c.is_ascii_digit() && *c != ' ')
+        {
+            return Err(unexpected(line, pos, c, "Line not a number."));
+        }
+    }
+    if let Some((pos, c)) = symbols
+        .char_indices()
+        .find(|(_, c)| !matches!(c, '+' | '*' | ' '))
+    {
+        return Err(unexpected(symbols, pos, c, "Unknown math symbol."));
+    }
+    Ok(input.to_owned())
 }
 
 #[aoc(day6, part1)]
-fn part1(input: &str) -> i64 {
+pub fn part1(input: &str) -> i64 {
     let depth = input.lines().count();
     let numbers = input
         .lines()
@@ -62,25 +93,22 @@ fn part1(input: &str) -> i64 {
 }
 
 #[aoc(day6, part2)]
-fn part2(input: &str) -> i64 {
-    let depth = input.lines().count();
-    let input_grid = input
-        .lines()
-        .take(depth - 1)
-        .map(|line| line.chars().collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+pub fn part2(input: &str) -> i64 {
+    let mut lines = input.lines();
+    let symbols = lines.next_back().expect("Worksheet without symbols");
+    let numbers = lines.collect::<Vec<_>>().join("\n");
+    let input_grid =
+        Grid::parse_ragged(Day6::DAY, &numbers, ' ', Some).expect("Every character is a cell");
 
-    let mut symbols = input.lines().last().unwrap().chars().filter(|c| *c != ' ');
+    let mut symbols = symbols.chars().filter(|c| *c != ' ');
 
     let mut problems = Vec::new();
     let mut nums = Vec::new();
-    for w in 0..input_grid[0].len() {
-        let mut number = String::new();
-        for d in 0..(depth - 1) {
-            if input_grid[d][w].is_ascii_digit() {
-                number.push(input_grid[d][w]);
-            }
-        }
+    for column in input_grid.transpose().rows() {
+        let number = column
+            .iter()
+            .filter(|c| c.is_ascii_digit())
+            .collect::<String>();
         if !number.is_empty() {
             nums.push(number.parse::<i64>().expect("Number is not a number"));
         } else {
@@ -99,6 +127,28 @@ fn part2(input: &str) -> i64 {
     problems.iter().map(|prob| prob.compute()).sum()
 }
 
+pub struct Day6;
+
+impl Solver for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = String;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,13 +156,16 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "123 328  51 64 
+            part1(
+                &parse(
+                    "123 328  51 64 
  45 64  387 23 
   6 98  215 314
 *   +   *   +  
 "
-            )),
+                )
+                .unwrap()
+            ),
             4277556
         );
     }
@@ -120,12 +173,32 @@ mod tests {
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "123 328  51 64 
+            part2(
+                &parse(
+                    "123 328  51 64 
  45 64  387 23 
   6 98  215 314
 *   +   *   +  "
-            )),
+                )
+                .unwrap()
+            ),
+            3263827
+        );
+    }
+
+    #[test]
+    fn part2_example_with_trailing_newline() {
+        assert_eq!(
+            part2(
+                &parse(
+                    "123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+*   +   *   +  
+"
+                )
+                .unwrap()
+            ),
             3263827
         );
     }
@@ -3,41 +3,69 @@ use std::collections::HashSet;
 use aoc_runner_derive::{aoc, aoc_generator};
 use memoize::memoize;
 
+use crate::{error::ParseError, solver::Solver, utils::grid::Grid};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Start,
+    Splitter,
+}
+
 #[aoc_generator(day7)]
-fn parse(input: &str) -> (usize, usize, HashSet<(usize, usize)>) {
-    (
-        input
-            .chars()
-            .position(|c| c == 'S')
-            .expect("No start found"),
-        input.lines().count(),
-        input
-            .lines()
-            .enumerate()
-            .flat_map(|(r, line)| {
-                line.chars()
-                    .enumerate()
-                    .filter(|(_, c)| *c == '^')
-                    .map(move |(p, _)| (r, p))
-            })
-            .collect(),
-    )
+pub fn parse(input: &str) -> Result<Grid<Cell>, ParseError> {
+    let manifold = Grid::parse_ragged(Day7::DAY, input, Cell::Empty, |c| match c {
+        '.' => Some(Cell::Empty),
+        'S' => Some(Cell::Start),
+        '^' => Some(Cell::Splitter),
+        _ => None,
+    })?;
+    match manifold.find(|&cell| cell == Cell::Start) {
+        Some((_, 0)) => Ok(manifold),
+        _ => Err(ParseError::new(
+            Day7::DAY,
+            input,
+            input.lines().next().unwrap_or_default(),
+            "No start found",
+        )),
+    }
+}
+
+fn start(manifold: &Grid<Cell>) -> usize {
+    manifold
+        .find(|&cell| cell == Cell::Start)
+        .expect("The generator checks for a start.")
+        .0
+}
+
+fn is_splitter(manifold: &Grid<Cell>, pos: (usize, usize)) -> bool {
+    manifold.get(pos) == Some(&Cell::Splitter)
 }
 
 #[aoc(day7, part1)]
-fn part1(input: &(usize, usize, HashSet<(usize, usize)>)) -> usize {
-    let (start, max_depth, map) = input;
+pub fn part1(input: &Grid<Cell>) -> usize {
+    part1_observed(input, |_| {})
+}
 
+/// The beams leaving one row of the manifold.
+pub struct Beams<'a> {
+    pub depth: usize,
+    pub tachyons: &'a HashSet<usize>,
+    pub splits: usize,
+}
+
+/// Solves part 1, reporting the beams of every row to `observer`.
+pub fn part1_observed(input: &Grid<Cell>, mut observer: impl FnMut(Beams<'_>)) -> usize {
     let mut tachyons = HashSet::new();
-    tachyons.insert(*start);
+    tachyons.insert(start(input));
 
     let mut splits = 0;
 
-    for depth in 1..*max_depth {
+    for depth in 1..input.height() {
         tachyons = tachyons
             .iter()
             .flat_map(|tachyon| {
-                if map.contains(&(depth, *tachyon)) {
+                if is_splitter(input, (*tachyon, depth)) {
                     splits += 1;
                     vec![*tachyon - 1, *tachyon + 1]
                 } else {
@@ -45,27 +73,55 @@ fn part1(input: &(usize, usize, HashSet<(usize, usize)>)) -> usize {
                 }
             })
             .collect::<HashSet<_>>();
+        observer(Beams {
+            depth,
+            tachyons: &tachyons,
+            splits,
+        });
     }
 
     splits
 }
 
-#[memoize(Ignore: map, Ignore: max_depth)]
-fn split(pos: usize, depth: usize, max_depth: usize, map: &HashSet<(usize, usize)>) -> usize {
-    if depth == max_depth {
+#[memoize(Ignore: map)]
+fn split(pos: usize, depth: usize, map: &Grid<Cell>) -> usize {
+    if depth == map.height() {
         return 1;
     }
-    if map.contains(&(depth, pos)) {
-        split(pos - 1, depth + 1, max_depth, map) + split(pos + 1, depth + 1, max_depth, map)
+    if is_splitter(map, (pos, depth)) {
+        split(pos - 1, depth + 1, map) + split(pos + 1, depth + 1, map)
     } else {
-        split(pos, depth + 1, max_depth, map)
+        split(pos, depth + 1, map)
     }
 }
 
 #[aoc(day7, part2)]
-fn part2(input: &(usize, usize, HashSet<(usize, usize)>)) -> usize {
-    let (start, max_depth, map) = input;
-    split(*start, 1, *max_depth, map)
+pub fn part2(input: &Grid<Cell>) -> usize {
+    // The cache ignores the map, so results of a previous input must not be reused.
+    memoized_flush_split();
+    split(start(input), 1, input)
+}
+
+pub struct Day7;
+
+impl Solver for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Grid<Cell>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -75,8 +131,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                ".......S.......
+            part1(
+                &parse(
+                    ".......S.......
 ...............
 .......^.......
 ...............
@@ -92,16 +149,29 @@ mod tests {
 ...............
 .^.^.^.^.^...^.
 .............."
-            )),
+                )
+                .unwrap()
+            ),
             21
         );
     }
 
+    #[test]
+    fn part2_does_not_reuse_previous_input() {
+        // The same positions lead to splitters in one manifold and to empty cells in the other.
+        let split = parse("..S..\n.....\n..^..\n.....").unwrap();
+        let straight = parse("..S..\n.....\n.....\n.....").unwrap();
+        assert_eq!(part2(&split), 2);
+        assert_eq!(part2(&straight), 1);
+        assert_eq!(part2(&split), 2);
+    }
+
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                ".......S.......
+            part2(
+                &parse(
+                    ".......S.......
 ...............
 .......^.......
 ...............
@@ -117,7 +187,9 @@ mod tests {
 ...............
 .^.^.^.^.^...^.
 .............."
-            )),
+                )
+                .unwrap()
+            ),
             40
         );
     }
@@ -1,17 +1,22 @@
-use anyhow::Result;
 use itertools::Itertools;
 use std::{collections::HashSet, str::FromStr};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
 #[derive(Debug)]
-struct JunctionBox {
+pub struct JunctionBox {
     x: isize,
     y: isize,
     z: isize,
 }
 
 impl JunctionBox {
+    pub fn position(&self) -> (isize, isize, isize) {
+        (self.x, self.y, self.z)
+    }
+
     fn distance_sq(&self, other: &Self) -> isize {
         let dx = self.x - other.x;
         let dy = self.y - other.y;
@@ -21,26 +26,20 @@ impl JunctionBox {
 }
 
 impl FromStr for JunctionBox {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut coords = s
-            .splitn(3, ',')
-            .map(|n| n.parse::<isize>().expect("Coordinate not a number"));
-        Ok(JunctionBox {
-            x: coords.next().unwrap(),
-            y: coords.next().unwrap(),
-            z: coords.next().unwrap(),
-        })
+        let [x, y, z] = Parser::new(Day8::DAY, s).tuple(s, ",")?;
+        Ok(JunctionBox { x, y, z })
     }
 }
 
 #[aoc_generator(day8)]
-fn parse(input: &str) -> Result<Vec<JunctionBox>> {
+pub fn parse(input: &str) -> Result<Vec<JunctionBox>, ParseError> {
     input
         .lines()
-        .map(JunctionBox::from_str)
-        .collect::<Result<Vec<_>>>()
+        .map(|line| JunctionBox::from_str(line).map_err(|err| err.relative_to(input, line)))
+        .collect::<Result<Vec<_>, _>>()
 }
 
 struct UnionFind {
@@ -101,7 +100,7 @@ fn get_sorted_junction_pairs(input: &[JunctionBox]) -> Vec<(usize, usize)> {
 }
 
 #[aoc(day8, part1)]
-fn part1(input: &[JunctionBox]) -> usize {
+pub fn part1(input: &[JunctionBox]) -> usize {
     let mut union_find = UnionFind::new(input.len());
 
     get_sorted_junction_pairs(input)
@@ -128,12 +127,27 @@ fn part1(input: &[JunctionBox]) -> usize {
 }
 
 #[aoc(day8, part2)]
-fn part2(input: &[JunctionBox]) -> isize {
+pub fn part2(input: &[JunctionBox]) -> isize {
+    part2_observed(input, |_| {})
+}
+
+/// A cable between two junction boxes, given by their index, that joins two circuits.
+pub struct Connection {
+    pub boxes: (usize, usize),
+    pub circuits: usize,
+}
+
+/// Solves part 2, reporting every cable that joins two circuits to `observer`.
+pub fn part2_observed(input: &[JunctionBox], mut observer: impl FnMut(Connection)) -> isize {
     let mut union_find = UnionFind::new(input.len());
     let mut junctions = input.len();
     for (id1, id2) in get_sorted_junction_pairs(input) {
         if union_find.union(id1, id2) {
             junctions -= 1;
+            observer(Connection {
+                boxes: (id1, id2),
+                circuits: junctions,
+            });
             if junctions == 1 {
                 return input[id1].x * input[id2].x;
             }
@@ -144,6 +158,28 @@ fn part2(input: &[JunctionBox]) -> isize {
     0
 }
 
+pub struct Day8;
+
+impl Solver for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<JunctionBox>;
+    type Part1 = usize;
+    type Part2 = isize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,18 +2,18 @@ use itertools::Itertools;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::{error::ParseError, solver::Solver, utils::parse::Parser};
+
 #[aoc_generator(day9)]
-fn parse(input: &str) -> Vec<(isize, isize)> {
+pub fn parse(input: &str) -> Result<Vec<(isize, isize)>, ParseError> {
+    let parser = Parser::new(Day9::DAY, input);
     input
         .lines()
-        .map(|line| line.split_once(',').expect("Coordinate without a comma."))
-        .map(|(x, y)| {
-            (
-                x.parse::<isize>().expect("X coordinate is not a number."),
-                y.parse::<isize>().expect("Y coordinate is not a number."),
-            )
+        .map(|line| {
+            let [x, y] = parser.tuple(line, ",")?;
+            Ok((x, y))
         })
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, _>>()
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -38,14 +38,57 @@ impl Rectangle {
         (self.x_max - self.x_min + 1) * (self.y_max - self.y_min + 1)
     }
 
-    fn within_corners(&self, corner1: &(isize, isize), corner2: &(isize, isize)) -> bool {
-        (corner1.0.max(corner2.0) <= self.x_min || corner1.0.min(corner2.0) >= self.x_max)
-            || (corner1.1.max(corner2.1) <= self.y_min || corner1.1.min(corner2.1) >= self.y_max)
+    /// Whether the segment between `end1` and `end2` shares no tile with the rectangle.
+    fn clear_of(&self, end1: &(isize, isize), end2: &(isize, isize)) -> bool {
+        (end1.0.max(end2.0) < self.x_min || end1.0.min(end2.0) > self.x_max)
+            || (end1.1.max(end2.1) < self.y_min || end1.1.min(end2.1) > self.y_max)
     }
 }
 
+/// The tiles right outside of the loop, as segments along its edges.
+///
+/// The tiles next to an edge on its outer side are outside of the loop, unless another edge runs
+/// through them, as for two edges one tile apart. A rectangle between red tiles that contains
+/// any tile outside of the loop also contains one of these, where it leaves the loop.
+fn outside(polygon: &[(isize, isize)]) -> Vec<[(isize, isize); 2]> {
+    let edges = polygon
+        .iter()
+        .copied()
+        .circular_tuple_windows()
+        .collect::<Vec<_>>();
+    // Twice the signed area, positive if the outside lies to the right of every edge.
+    let area = edges
+        .iter()
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<isize>();
+    let mut segments = Vec::new();
+    for &(a, b) in &edges {
+        let (dx, dy) = ((b.0 - a.0).signum(), (b.1 - a.1).signum());
+        let (nx, ny) = if area > 0 { (dy, -dx) } else { (-dy, dx) };
+        // Works along rows, with columns transposed.
+        let horizontal = a.1 == b.1;
+        let along = |(x, y): (isize, isize)| if horizontal { (x, y) } else { (y, x) };
+        let (from, to) = (along((a.0 + nx, a.1 + ny)), along((b.0 + nx, b.1 + ny)));
+        let (row, end) = (from.1, from.0.max(to.0));
+        let blocked = edges
+            .iter()
+            .map(|&(c, d)| (along(c), along(d)))
+            .filter(|(c, d)| (c.1.min(d.1)..=c.1.max(d.1)).contains(&row))
+            .map(|(c, d)| (c.0.min(d.0), c.0.max(d.0)))
+            .sorted();
+        let mut next = from.0.min(to.0);
+        for (start, stop) in blocked.chain([(end + 1, end + 1)]) {
+            if start > next && next <= end {
+                segments.push([along((next, row)), along(((start - 1).min(end), row))]);
+            }
+            next = next.max(stop + 1);
+        }
+    }
+    segments
+}
+
 #[aoc(day9, part1)]
-fn part1(input: &[(isize, isize)]) -> isize {
+pub fn part1(input: &[(isize, isize)]) -> isize {
     input
         .iter()
         .combinations(2)
@@ -55,24 +98,69 @@ fn part1(input: &[(isize, isize)]) -> isize {
 }
 
 #[aoc(day9, part2)]
-fn part2(input: &[(isize, isize)]) -> isize {
+pub fn part2(input: &[(isize, isize)]) -> isize {
+    part2_observed(input, |_| {}).unwrap()
+}
+
+/// A rectangle within the red and green tiles that is larger than every one before it.
+pub struct Candidate {
+    pub corners: [(isize, isize); 2],
+    pub area: isize,
+}
+
+/// Solves part 2, reporting every new largest rectangle to `observer`.
+///
+/// Returns `None` if no rectangle lies within the loop, as with fewer than two red tiles.
+pub fn part2_observed(
+    input: &[(isize, isize)],
+    mut observer: impl FnMut(Candidate),
+) -> Option<isize> {
+    let outside = outside(input);
     input
         .iter()
         .combinations(2)
         //  A---B
         //  |   |
         //  C---D
-        .map(|tile_pair| Rectangle::new(tile_pair[0], tile_pair[1]))
-        .filter(|rectangle| {
-            input
+        .map(|tile_pair| (Rectangle::new(tile_pair[0], tile_pair[1]), tile_pair))
+        .filter(|(rectangle, _)| {
+            outside
                 .iter()
-                .chain(&[input[0]])
-                .tuple_windows()
-                .all(|(corner1, corner2)| rectangle.within_corners(corner1, corner2))
+                .all(|[end1, end2]| rectangle.clear_of(end1, end2))
         })
-        .map(|rectangle| rectangle.area())
-        .max()
-        .unwrap()
+        .fold(None, |best, (rectangle, tile_pair)| {
+            let area = rectangle.area();
+            if best.is_some_and(|best| best >= area) {
+                return best;
+            }
+            observer(Candidate {
+                corners: [*tile_pair[0], *tile_pair[1]],
+                area,
+            });
+            Some(area)
+        })
+}
+
+pub struct Day9;
+
+impl Solver for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<(isize, isize)>;
+    type Part1 = isize;
+    type Part2 = isize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Part1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Part2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -82,8 +170,9 @@ mod tests {
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                "7,1
+            part1(
+                &parse(
+                    "7,1
 11,1
 11,7
 9,7
@@ -92,16 +181,42 @@ mod tests {
 2,3
 7,3
 "
-            )),
+                )
+                .unwrap()
+            ),
             50
         );
     }
 
+    #[test]
+    fn part2_ignores_rectangles_outside() {
+        // No edge crosses the rectangle from 16,6 to 30,18, but it lies outside of the loop.
+        assert_eq!(
+            part2(&parse("8,22\n16,22\n16,24\n30,24\n30,18\n16,18\n16,6\n8,6").unwrap()),
+            153
+        );
+    }
+
+    #[test]
+    fn part2_spans_edges_one_tile_apart() {
+        // The edges at x=15 and x=16 leave no tile between them, so rectangles may cross them.
+        assert_eq!(
+            part2(
+                &parse(
+                    "9,19\n11,19\n11,16\n15,16\n15,13\n16,13\n16,18\n20,18\n20,5\n16,5\n16,4\n15,4\n15,6\n11,6\n11,4\n9,4"
+                )
+                .unwrap()
+            ),
+            72
+        );
+    }
+
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "7,1
+            part2(
+                &parse(
+                    "7,1
 11,1
 11,7
 9,7
@@ -110,7 +225,9 @@ mod tests {
 2,3
 7,3
 "
-            )),
+                )
+                .unwrap()
+            ),
             24
         );
     }
@@ -0,0 +1,151 @@
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    generate::{self, Rng},
+    reference,
+    runner::{self, Parts},
+};
+
+/// An input on which a solver and its reference solver give different answers.
+#[derive(Debug)]
+pub struct Disagreement {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+    pub expected: String,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {} - part {}: answered {}, reference answered {} for input:\n{}",
+            self.day, self.part, self.answer, self.expected, self.input
+        )
+    }
+}
+
+/// Generates an input for `day` that is small enough for the reference solvers.
+pub fn small_input(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+    Some(match day {
+        1 => generate::day1(rng, size, 300),
+        2 => generate::day2(rng, size, 6, 200),
+        3 => generate::day3(rng, size, 12 + size),
+        4 => generate::day4(rng, size, size, 60),
+        5 => generate::day5(rng, size, size, 50),
+        6 => generate::day6(rng, size, 3, 3),
+        7 => generate::day7(rng, 2 * size + 1, 2 * size, 40),
+        8 => generate::day8(rng, size + 1, 20),
+        9 => generate::day9(rng, size, 20),
+        10 => generate::day10(rng, size, 5, 5),
+        11 => generate::day11(rng, size + 5, 3),
+        12 => generate::day12(rng, size, 6, 4),
+        _ => return None,
+    })
+}
+
+/// Compares the solver of `day` with its reference on `input`.
+///
+/// Inputs the reference rejects are not compared. A solver that fails or panics disagrees.
+pub fn compare(day: u8, input: &str) -> Option<Disagreement> {
+    let expected = reference::get(day)?(input).ok()?;
+    let answers =
+        match panic::catch_unwind(AssertUnwindSafe(|| runner::solve(day, Parts::All, input))) {
+            Ok(Ok(solution)) => solution
+                .answers
+                .into_iter()
+                .map(|answer| answer.value)
+                .collect(),
+            Ok(Err(err)) => vec![format!("error: {err:#}"); 2],
+            Err(_) => vec!["panic".to_owned(); 2],
+        };
+    answers
+        .into_iter()
+        .zip(expected)
+        .enumerate()
+        .find(|(_, (answer, expected))| answer != expected)
+        .map(|(part, (answer, expected))| Disagreement {
+            day,
+            part: part as u8 + 1,
+            input: input.to_owned(),
+            answer,
+            expected,
+        })
+}
+
+/// Removes lines, or comma separated ranges on day 2, for as long as the disagreement remains.
+pub fn shrink(disagreement: Disagreement) -> Disagreement {
+    shrink_with(disagreement, compare)
+}
+
+fn shrink_with(
+    mut disagreement: Disagreement,
+    compare: impl Fn(u8, &str) -> Option<Disagreement>,
+) -> Disagreement {
+    let separator = if disagreement.day == 2 { "," } else { "\n" };
+    'shrinking: loop {
+        let records = disagreement.input.split(separator).collect::<Vec<_>>();
+        for skip in 0..records.len() {
+            let candidate = records
+                .iter()
+                .enumerate()
+                .filter(|&(pos, _)| pos != skip)
+                .map(|(_, record)| *record)
+                .collect::<Vec<_>>()
+                .join(separator);
+            if let Some(smaller) = compare(disagreement.day, &candidate) {
+                disagreement = smaller;
+                continue 'shrinking;
+            }
+        }
+        return disagreement;
+    }
+}
+
+/// Compares the solver of `day` with its reference on generated inputs of growing size, trying
+/// every seed in `seeds` per size, and returns the first disagreement shrunk to a minimal input.
+pub fn run(day: u8, seeds: u64, max_size: usize) -> Option<Disagreement> {
+    (1..=max_size)
+        .flat_map(|size| (0..seeds).map(move |seed| (seed, size)))
+        .filter_map(|(seed, size)| small_input(day, seed, size))
+        .find_map(|input| compare(day, &input))
+        .map(shrink)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solvers_agree_with_references() {
+        for day in 1..=12 {
+            if let Some(disagreement) = run(day, 20, 6) {
+                panic!("{disagreement}");
+            }
+        }
+    }
+
+    #[test]
+    fn shrinks_to_minimal_input() {
+        // A solver that goes wrong whenever the dial turns left by 5 and right by 7.
+        let compare = |day, input: &str| {
+            let wrong = input.contains("L5") && input.contains("R7");
+            wrong.then(|| Disagreement {
+                day,
+                part: 2,
+                input: input.to_owned(),
+                answer: "0".to_owned(),
+                expected: "1".to_owned(),
+            })
+        };
+        let disagreement = compare(1, "R1\nL5\nR2\nL3\nR7\nL9").unwrap();
+        assert_eq!(shrink_with(disagreement, compare).input, "L5\nR7");
+        assert!(compare(1, "L5\nR6").is_none());
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// A malformed puzzle input, pointing at the offending text.
+///
+/// Lines and columns are counted from 1, columns in characters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    /// Creates an error for `token`, which has to be a slice of `source`.
+    ///
+    /// The position is taken from where `token` lies within `source`. Tokens that are not part
+    /// of `source` are reported at its end.
+    pub fn new(day: u8, source: &str, token: &str, reason: impl Into<String>) -> Self {
+        let (line, column) = locate(source, token);
+        Self {
+            day,
+            line,
+            column,
+            text: token.to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Moves an error created for `source` to its position within `input`, of which `source`
+    /// has to be a slice.
+    ///
+    /// Used when a part of the input, e.g. a single line, is parsed on its own through `FromStr`.
+    pub fn relative_to(mut self, input: &str, source: &str) -> Self {
+        let (line, column) = locate(input, source);
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self.line += line - 1;
+        self
+    }
+}
+
+fn locate(source: &str, token: &str) -> (usize, usize) {
+    let mut offset = (token.as_ptr() as usize)
+        .saturating_sub(source.as_ptr() as usize)
+        .min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {}, line {}, column {}: {} (found {:?})",
+            self.day, self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_token() {
+        let input = "1-2\n3-x4\n";
+        let err = ParseError::new(5, input, &input[6..8], "Not a number");
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x4"));
+        assert_eq!(
+            err.to_string(),
+            "Day 5, line 2, column 3: Not a number (found \"x4\")"
+        );
+    }
+
+    #[test]
+    fn relocates_error_of_sub_slice() {
+        let input = "11-22,95-x";
+        let source = &input[6..];
+        let err =
+            ParseError::new(2, source, &source[3..], "Not a number").relative_to(input, source);
+        assert_eq!((err.line, err.column), (1, 10));
+    }
+}
@@ -0,0 +1,370 @@
+use std::{collections::HashSet, fmt::Write, ops::RangeInclusive};
+
+use itertools::Itertools;
+
+/// Small deterministic SplitMix64 generator, so generated inputs only depend on the seed.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly picks a number from `range`.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Empty range {start}..={end}");
+        let span = (end - start) as u128 + 1;
+        start + ((self.next_u64() as u128 * span) >> 64) as u64
+    }
+
+    /// Uniformly picks an index below `n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..=(n as u64 - 1)) as usize
+    }
+
+    /// Returns true with a chance of `percent` out of 100.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(1..=100) <= percent
+    }
+}
+
+/// Generates an input for `day` from `seed`, with `size` roughly being the number of lines.
+pub fn generate(day: u8, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    let size = size.max(1);
+    Some(match day {
+        1 => day1(rng, size, 1000),
+        2 => day2(rng, size, 10, 1000),
+        3 => day3(rng, size, 12 + size),
+        4 => day4(rng, size, size, 60),
+        5 => day5(rng, size, size, 100 * size as u64),
+        6 => day6(rng, size, 4, 3),
+        7 => day7(rng, 2 * size + 1, 2 * size, 40),
+        8 => day8(rng, size + 1, 1000),
+        9 => day9(rng, size, 100),
+        10 => day10(rng, size, 8, 8),
+        11 => day11(rng, size + 5, 3),
+        12 => day12(rng, size, 50, 500),
+        _ => return None,
+    })
+}
+
+/// `rotations` dial rotations of up to `max_clicks` clicks each.
+pub fn day1(rng: &mut Rng, rotations: usize, max_clicks: u64) -> String {
+    (0..rotations)
+        .map(|_| {
+            let direction = if rng.chance(50) { 'L' } else { 'R' };
+            format!("{direction}{}", rng.range(1..=max_clicks))
+        })
+        .join("\n")
+}
+
+/// `ranges` ID ranges with starts of up to `max_digits` digits, each spanning at most `max_width`
+/// IDs.
+pub fn day2(rng: &mut Rng, ranges: usize, max_digits: u32, max_width: u64) -> String {
+    (0..ranges)
+        .map(|_| {
+            let digits = rng.range(1..=max_digits as u64) as u32;
+            let start = rng.range(10u64.pow(digits - 1)..=(10u64.pow(digits) - 1));
+            format!("{start}-{}", start + rng.range(0..=max_width))
+        })
+        .join(",")
+}
+
+/// `banks` battery banks of `batteries` joltages from 1 to 9.
+pub fn day3(rng: &mut Rng, banks: usize, batteries: usize) -> String {
+    (0..banks)
+        .map(|_| (0..batteries).map(|_| rng.range(1..=9)).join(""))
+        .join("\n")
+}
+
+/// A `width` by `height` grid of paper rolls, each cell holding one with `density` percent.
+pub fn day4(rng: &mut Rng, width: usize, height: usize, density: u64) -> String {
+    (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| if rng.chance(density) { '@' } else { '.' })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// `ranges` fresh ingredient ranges and `ingredients` available ingredients with IDs up to
+/// `max_id`.
+pub fn day5(rng: &mut Rng, ranges: usize, ingredients: usize, max_id: u64) -> String {
+    let fresh = (0..ranges.max(1)).map(|_| {
+        let start = rng.range(1..=max_id);
+        format!("{start}-{}", rng.range(start..=max_id))
+    });
+    let fresh = fresh.collect::<Vec<_>>();
+    let available = (0..ingredients).map(|_| rng.range(1..=max_id)).join("\n");
+    format!("{}\n\n{available}", fresh.join("\n"))
+}
+
+/// A worksheet of `problems` problems with `rows` numbers of up to `max_digits` digits each.
+///
+/// Numbers of a problem are randomly aligned left or right within its column block.
+pub fn day6(rng: &mut Rng, problems: usize, rows: usize, max_digits: u32) -> String {
+    let mut lines = vec![String::new(); rows + 1];
+    for problem in 0..problems.max(1) {
+        let numbers = (0..rows.max(1))
+            .map(|_| {
+                let digits = rng.range(1..=max_digits as u64) as u32;
+                rng.range(1..=(10u64.pow(digits) - 1)).to_string()
+            })
+            .collect::<Vec<_>>();
+        let width = numbers.iter().map(String::len).max().unwrap();
+        let left_aligned = rng.chance(50);
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            if problem > 0 {
+                line.push(' ');
+            }
+            if left_aligned {
+                write!(line, "{number:<width$}").unwrap();
+            } else {
+                write!(line, "{number:>width$}").unwrap();
+            }
+        }
+        let symbols = lines.last_mut().unwrap();
+        if problem > 0 {
+            symbols.push(' ');
+        }
+        let operation = if rng.chance(50) { '+' } else { '*' };
+        write!(symbols, "{operation:<width$}").unwrap();
+    }
+    lines.join("\n")
+}
+
+/// A tachyon manifold `width` wide and `depth` deep, with the start in the middle of the top row.
+///
+/// Every other row holds splitters, each cell with `density` percent. The outermost columns never
+/// hold splitters, so beams can not leave the manifold.
+pub fn day7(rng: &mut Rng, width: usize, depth: usize, density: u64) -> String {
+    let width = width.max(3);
+    let mut rows = vec![format!(
+        "{}S{}",
+        ".".repeat(width / 2),
+        ".".repeat(width - width / 2 - 1)
+    )];
+    rows.extend((1..depth.max(2)).map(|row| {
+        (0..width)
+            .map(|col| {
+                let inner = col > 0 && col < width - 1;
+                if row % 2 == 0 && inner && rng.chance(density) {
+                    '^'
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>()
+    }));
+    rows.join("\n")
+}
+
+/// `boxes` junction boxes with coordinates up to `max_coord`.
+pub fn day8(rng: &mut Rng, boxes: usize, max_coord: u64) -> String {
+    (0..boxes.max(2))
+        .map(|_| (0..3).map(|_| rng.range(0..=max_coord)).join(","))
+        .join("\n")
+}
+
+/// Picks `n` distinct sorted values from `range`, which has to hold at least `n` values.
+fn distinct_sorted(rng: &mut Rng, n: usize, range: RangeInclusive<u64>) -> Vec<u64> {
+    let mut values = HashSet::new();
+    while values.len() < n {
+        values.insert(rng.range(range.clone()));
+    }
+    values.into_iter().sorted().collect()
+}
+
+/// Picks `n` values from `range` where neighbours always differ.
+fn changing(rng: &mut Rng, n: usize, range: RangeInclusive<u64>) -> Vec<u64> {
+    let mut values: Vec<u64> = Vec::with_capacity(n);
+    while values.len() < n {
+        let value = rng.range(range.clone());
+        if values.last() != Some(&value) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// The red tiles of a rectilinear polygon with `columns` columns and coordinates up to
+/// `max_coord`, listed in order around the polygon.
+///
+/// The polygon is bounded by a stepped top and a stepped bottom profile over shared column
+/// borders, so it is always simple and consecutive tiles always turn.
+pub fn day9(rng: &mut Rng, columns: usize, max_coord: u64) -> String {
+    let max_coord = max_coord.max(4);
+    let columns = columns.clamp(1, max_coord as usize);
+    let mid = max_coord / 2;
+    let xs = distinct_sorted(rng, columns + 1, 0..=max_coord);
+    let tops = changing(rng, columns, (mid + 1)..=max_coord);
+    let bottoms = changing(rng, columns, 0..=(mid - 1));
+
+    let mut tiles = Vec::new();
+    for (i, top) in tops.iter().enumerate() {
+        tiles.push((xs[i], *top));
+        tiles.push((xs[i + 1], *top));
+    }
+    for (i, bottom) in bottoms.iter().enumerate().rev() {
+        tiles.push((xs[i + 1], *bottom));
+        tiles.push((xs[i], *bottom));
+    }
+    tiles.iter().map(|(x, y)| format!("{x},{y}")).join("\n")
+}
+
+/// `machines` machines with up to `max_lights` lights and up to `max_buttons` buttons.
+///
+/// The target lights and joltages are produced by pressing random buttons, so every machine is
+/// solvable.
+pub fn day10(rng: &mut Rng, machines: usize, max_lights: usize, max_buttons: usize) -> String {
+    (0..machines)
+        .map(|_| {
+            let lights = rng.range(1..=max_lights.clamp(1, 10) as u64) as usize;
+            let buttons = (0..rng.range(1..=max_buttons.max(1) as u64))
+                .map(|_| {
+                    let button = (0..lights).filter(|_| rng.chance(40)).collect::<Vec<_>>();
+                    if button.is_empty() {
+                        vec![rng.below(lights)]
+                    } else {
+                        button
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut target = vec![false; lights];
+            let mut joltages = vec![0; lights];
+            for button in &buttons {
+                if rng.chance(50) {
+                    button.iter().for_each(|&light| target[light] ^= true);
+                }
+                let presses = rng.range(0..=10);
+                button.iter().for_each(|&light| joltages[light] += presses);
+            }
+            format!(
+                "[{}] {} {{{}}}",
+                target
+                    .iter()
+                    .map(|&on| if on { '#' } else { '.' })
+                    .collect::<String>(),
+                buttons
+                    .iter()
+                    .map(|button| format!("({})", button.iter().join(",")))
+                    .join(" "),
+                joltages.iter().join(",")
+            )
+        })
+        .join("\n")
+}
+
+/// A directed acyclic device graph with `devices` devices of up to `max_outputs` outputs each.
+///
+/// Contains the devices `svr`, `you`, `dac`, `fft` and `out` in that topological order, and
+/// every device but `out` is connected to the device following it in that order.
+pub fn day11(rng: &mut Rng, devices: usize, max_outputs: usize) -> String {
+    let reserved = ["svr", "you", "dac", "fft", "out"];
+    let devices = devices.max(reserved.len());
+    let mut names = HashSet::<String>::from_iter(reserved.map(str::to_owned));
+    let mut order = Vec::new();
+    while names.len() < devices {
+        let name = (0..3)
+            .map(|_| (b'a' + rng.below(26) as u8) as char)
+            .collect::<String>();
+        if names.insert(name.clone()) {
+            order.push(name);
+        }
+    }
+    // Place the special devices in order at random spots, with `out` always last.
+    for (rank, name) in reserved.iter().take(4).enumerate() {
+        let lower = order
+            .iter()
+            .position(|device| device == reserved[rank.saturating_sub(1)])
+            .map_or(0, |pos| pos + 1);
+        let pos = rng.range(lower as u64..=order.len() as u64) as usize;
+        order.insert(pos, name.to_string());
+    }
+    order.push("out".to_owned());
+
+    (0..(order.len() - 1))
+        .map(|from| {
+            // Always connecting to the next device keeps `svr`, `dac`, `fft` and `out` connected.
+            let outputs = std::iter::once(from + 1)
+                .chain(
+                    (1..rng.range(1..=max_outputs.max(1) as u64))
+                        .map(|_| rng.range((from as u64 + 1)..=(order.len() as u64 - 1)) as usize),
+                )
+                .unique()
+                .map(|to| order[to].as_str())
+                .join(" ");
+            format!("{}: {outputs}", order[from])
+        })
+        .join("\n")
+}
+
+/// Six random three by three present shapes and `regions` regions up to `max_size` wide and high,
+/// each asking for at most `max_presents` presents.
+pub fn day12(rng: &mut Rng, regions: usize, max_size: u64, max_presents: u64) -> String {
+    let shapes = (0..6).map(|index| {
+        let mut cells = (0..9).map(|_| rng.chance(70)).collect::<Vec<_>>();
+        cells[4] = true;
+        let rows = cells
+            .chunks(3)
+            .map(|row| {
+                row.iter()
+                    .map(|&c| if c { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .join("\n");
+        format!("{index}:\n{rows}")
+    });
+    let shapes = shapes.collect::<Vec<_>>();
+    let regions = (0..regions.max(1))
+        .map(|_| {
+            let (width, height) = (rng.range(3..=max_size), rng.range(3..=max_size));
+            // Keeps the number of presents plausible for the area of the region.
+            let presents = rng.range(0..=max_presents.min(width * height / 5));
+            let mut counts = [0; 6];
+            (0..presents).for_each(|_| counts[rng.below(6)] += 1);
+            let counts = counts.iter().join(" ");
+            format!("{width}x{height}: {counts}")
+        })
+        .join("\n");
+    format!("{}\n\n{regions}", shapes.join("\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver;
+
+    #[test]
+    fn generators_are_deterministic() {
+        assert_eq!(generate(4, 7, 10), generate(4, 7, 10));
+        assert_ne!(generate(4, 7, 10), generate(4, 8, 10));
+        assert_eq!(generate(13, 7, 10), None);
+    }
+
+    #[test]
+    fn generated_inputs_are_solvable() {
+        for puzzle in solver::puzzles() {
+            for seed in 0..5 {
+                let input = generate(puzzle.day(), seed, 8).unwrap();
+                let parsed = puzzle
+                    .parse(&input)
+                    .unwrap_or_else(|err| panic!("{err:#} in generated input:\n{input}"));
+                puzzle.part1(&parsed).unwrap();
+                puzzle.part2(&parsed).unwrap();
+            }
+        }
+    }
+}
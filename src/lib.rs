@@ -1,18 +1,27 @@
-mod day11;
-mod day12;
-#[macro_use]
-mod utils;
+pub mod day11;
+pub mod day12;
+pub mod utils;
 use aoc_runner_derive::aoc_lib;
 
-mod day1;
-mod day10;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+pub mod day1;
+pub mod day10;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+pub mod bench;
+pub mod differential;
+pub mod error;
+pub mod generate;
+pub mod reference;
+pub mod runner;
+pub mod solver;
+pub mod verify;
+pub mod visualize;
 
 aoc_lib! { year = 2025 }
@@ -0,0 +1,640 @@
+//! Slow but straightforward solutions, used to cross-check the real ones on small inputs.
+//!
+//! Every solver parses the raw input on its own and returns the answers of both parts. Inputs
+//! outside of what the puzzle allows are rejected with an error instead of being guessed at.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::{Context, Result, bail, ensure};
+use itertools::Itertools;
+
+pub type Reference = fn(&str) -> Result<[String; 2]>;
+
+/// Looks up the reference solver of `day`.
+pub fn get(day: u8) -> Option<Reference> {
+    Some(match day {
+        1 => day1,
+        2 => day2,
+        3 => day3,
+        4 => day4,
+        5 => day5,
+        6 => day6,
+        7 => day7,
+        8 => day8,
+        9 => day9,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        _ => return None,
+    })
+}
+
+fn answers(part1: impl ToString, part2: impl ToString) -> [String; 2] {
+    [part1.to_string(), part2.to_string()]
+}
+
+/// Turns the dial one click at a time.
+pub fn day1(input: &str) -> Result<[String; 2]> {
+    let mut dial = 50i64;
+    let (mut landed, mut passed) = (0, 0);
+    for line in input.lines() {
+        let (step, clicks) = match line.split_at_checked(1) {
+            Some(("L", clicks)) => (-1, clicks),
+            Some(("R", clicks)) => (1, clicks),
+            _ => bail!("Unknown rotation {line:?}"),
+        };
+        for _ in 0..clicks.parse::<u64>()? {
+            dial = (dial + step).rem_euclid(100);
+            if dial == 0 {
+                passed += 1;
+            }
+        }
+        if dial == 0 {
+            landed += 1;
+        }
+    }
+    Ok(answers(landed, passed))
+}
+
+/// Checks the decimal string of every ID in every range.
+pub fn day2(input: &str) -> Result<[String; 2]> {
+    let (mut twice, mut repeated) = (0u64, 0u64);
+    for range in input.split(',') {
+        let (start, end) = range.split_once('-').context("Range without -")?;
+        let (start, end) = (start.parse::<u64>()?, end.parse::<u64>()?);
+        for id in start..=end {
+            let id_str = id.to_string();
+            let len = id_str.len();
+            let repeats = |block: usize| id_str == id_str[..block].repeat(len / block);
+            if len % 2 == 0 && repeats(len / 2) {
+                twice += id;
+            }
+            if (1..len).any(|block| len % block == 0 && repeats(block)) {
+                repeated += id;
+            }
+        }
+    }
+    Ok(answers(twice, repeated))
+}
+
+/// Picks the best digits with a table over every suffix of the bank and every number of digits.
+pub fn day3(input: &str) -> Result<[String; 2]> {
+    let largest = |bank: &[u64], digits: usize| {
+        // best[i][k]: largest k digit number from bank[i..], if there are enough batteries.
+        let mut best = vec![vec![None; digits + 1]; bank.len() + 1];
+        for i in (0..=bank.len()).rev() {
+            best[i][0] = Some(0);
+            for k in 1..=digits {
+                if i < bank.len() {
+                    let take =
+                        best[i + 1][k - 1].map(|rest| bank[i] * 10u64.pow(k as u32 - 1) + rest);
+                    best[i][k] = take.max(best[i + 1][k]);
+                }
+            }
+        }
+        best[0][digits]
+    };
+    let (mut two, mut twelve) = (0, 0);
+    for bank in input.lines() {
+        let bank = bank
+            .chars()
+            .map(|c| c.to_digit(10).map(u64::from).context("Not a digit"))
+            .collect::<Result<Vec<_>>>()?;
+        two += largest(&bank, 2).context("Bank with less than 2 batteries")?;
+        twelve += largest(&bank, 12).context("Bank with less than 12 batteries")?;
+    }
+    Ok(answers(two, twelve))
+}
+
+/// Removes accessible rolls one at a time until none is left.
+pub fn day4(input: &str) -> Result<[String; 2]> {
+    let mut rolls = HashSet::new();
+    for (y, row) in input.lines().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            match c {
+                '@' => {
+                    rolls.insert((x as isize, y as isize));
+                }
+                '.' => {}
+                _ => bail!("Unexpected character {c:?}"),
+            }
+        }
+    }
+    let accessible = |rolls: &HashSet<(isize, isize)>, &(x, y): &(isize, isize)| {
+        (-1..=1)
+            .cartesian_product(-1..=1)
+            .filter(|&(dx, dy)| (dx, dy) != (0, 0) && rolls.contains(&(x + dx, y + dy)))
+            .count()
+            < 4
+    };
+    let first = rolls.iter().filter(|roll| accessible(&rolls, roll)).count();
+    let mut removed = 0;
+    while let Some(roll) = rolls.iter().copied().find(|roll| accessible(&rolls, roll)) {
+        rolls.remove(&roll);
+        removed += 1;
+    }
+    Ok(answers(first, removed))
+}
+
+/// Lists every fresh ingredient ID.
+pub fn day5(input: &str) -> Result<[String; 2]> {
+    let (fresh, available) = input.split_once("\n\n").context("Empty line missing")?;
+    let mut fresh_ids = HashSet::new();
+    for range in fresh.lines() {
+        let (start, end) = range.split_once('-').context("Range without -")?;
+        let (start, end) = (start.parse::<u64>()?, end.parse::<u64>()?);
+        ensure!(end - start < 1_000_000, "Range too large for the reference");
+        fresh_ids.extend(start..=end);
+    }
+    ensure!(!fresh_ids.is_empty(), "No fresh ingredients");
+    let available = available
+        .lines()
+        .map(|id| id.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let fresh_available = available.iter().filter(|id| fresh_ids.contains(id)).count();
+    Ok(answers(fresh_available, fresh_ids.len()))
+}
+
+/// Reads the worksheet once by rows and once by columns, split at columns of only spaces.
+pub fn day6(input: &str) -> Result<[String; 2]> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let (symbols, rows) = lines.split_last().context("Empty worksheet")?;
+    ensure!(!rows.is_empty(), "Worksheet without numbers");
+    let width = lines.iter().map(|line| line.len()).max().unwrap();
+    let cell = |line: &str, col: usize| line.as_bytes().get(col).copied().unwrap_or(b' ');
+    let compute = |operation: u8, numbers: &[i128]| match operation {
+        b'+' => Ok(numbers.iter().sum::<i128>()),
+        b'*' => Ok(numbers.iter().product::<i128>()),
+        _ => bail!("Unknown operation {}", operation as char),
+    };
+
+    let (mut by_rows, mut by_columns) = (0, 0);
+    let blocks = (0..width)
+        .chunk_by(|&col| lines.iter().all(|line| cell(line, col) == b' '))
+        .into_iter()
+        .filter(|(blank, _)| !blank)
+        .map(|(_, block)| block.collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for block in blocks {
+        let operation = block
+            .iter()
+            .map(|&col| cell(symbols, col))
+            .find(|&c| c != b' ')
+            .context("Problem without operation")?;
+        let text = |line: &str| {
+            block
+                .iter()
+                .map(|&col| cell(line, col) as char)
+                .collect::<String>()
+        };
+        let row_numbers = rows
+            .iter()
+            .map(|row| text(row).trim().parse::<i128>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let column_numbers = block
+            .iter()
+            .map(|&col| {
+                rows.iter()
+                    .map(|row| cell(row, col) as char)
+                    .filter(|c| *c != ' ')
+                    .collect::<String>()
+                    .parse::<i128>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        by_rows += compute(operation, &row_numbers)?;
+        by_columns += compute(operation, &column_numbers)?;
+    }
+    Ok(answers(by_rows, by_columns))
+}
+
+/// Moves the beams down row by row, counting the timelines that reach every position.
+pub fn day7(input: &str) -> Result<[String; 2]> {
+    let rows = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+    let start = rows
+        .first()
+        .and_then(|row| row.iter().position(|&c| c == b'S'))
+        .context("No start found")?;
+    let mut timelines = HashMap::from([(start, 1u64)]);
+    let mut splits = 0;
+    for row in rows.iter().skip(1) {
+        let mut next = HashMap::new();
+        for (&pos, &count) in &timelines {
+            if row.get(pos) == Some(&b'^') {
+                ensure!(pos > 0 && pos + 1 < row.len(), "Beam leaves the manifold");
+                splits += 1;
+                *next.entry(pos - 1).or_default() += count;
+                *next.entry(pos + 1).or_default() += count;
+            } else {
+                *next.entry(pos).or_default() += count;
+            }
+        }
+        timelines = next;
+    }
+    Ok(answers(splits, timelines.values().sum::<u64>()))
+}
+
+/// Adds connections by increasing distance and finds circuits with a fresh search every time.
+pub fn day8(input: &str) -> Result<[String; 2]> {
+    let boxes = input
+        .lines()
+        .map(|line| {
+            let coords = line
+                .split(',')
+                .map(|n| n.parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()?;
+            ensure!(coords.len() == 3, "Expected three coordinates");
+            Ok(coords)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(boxes.len() >= 2, "Less than two junction boxes");
+    let distance =
+        |a: &[i64], b: &[i64]| a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum::<i64>();
+    let pairs = (0..boxes.len())
+        .tuple_combinations()
+        .sorted_by_key(|&(a, b)| (distance(&boxes[a], &boxes[b]), a, b))
+        .collect::<Vec<_>>();
+    let circuits = |connections: &[(usize, usize)]| {
+        let mut circuit = vec![None; boxes.len()];
+        let mut sizes = Vec::new();
+        for first in 0..boxes.len() {
+            if circuit[first].is_some() {
+                continue;
+            }
+            let mut queue = VecDeque::from([first]);
+            circuit[first] = Some(sizes.len());
+            let mut size = 0;
+            while let Some(current) = queue.pop_front() {
+                size += 1;
+                for &(a, b) in connections {
+                    for (from, to) in [(a, b), (b, a)] {
+                        if from == current && circuit[to].is_none() {
+                            circuit[to] = Some(sizes.len());
+                            queue.push_back(to);
+                        }
+                    }
+                }
+            }
+            sizes.push(size);
+        }
+        sizes
+    };
+
+    let first_connections = &pairs[..pairs.len().min(1000)];
+    let largest = circuits(first_connections)
+        .into_iter()
+        .sorted()
+        .rev()
+        .take(3)
+        .product::<usize>();
+    let last = (1..=pairs.len())
+        .find(|&n| circuits(&pairs[..n]).len() == 1)
+        .map(|n| pairs[n - 1])
+        .unwrap();
+    Ok(answers(largest, boxes[last.0][0] * boxes[last.1][0]))
+}
+
+/// Marks every tile inside the polygon and checks every tile of every rectangle.
+pub fn day9(input: &str) -> Result<[String; 2]> {
+    let tiles = input
+        .lines()
+        .map(|line| {
+            let (x, y) = line.split_once(',').context("Coordinate without a comma")?;
+            Ok((x.parse::<i64>()?, y.parse::<i64>()?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(tiles.len() >= 4, "Polygon with less than four corners");
+    let edges = tiles
+        .iter()
+        .copied()
+        .circular_tuple_windows()
+        .collect::<Vec<_>>();
+    ensure!(
+        edges.iter().all(|(a, b)| a.0 == b.0 || a.1 == b.1),
+        "Polygon is not rectilinear"
+    );
+    let (max_x, max_y) = (
+        tiles.iter().map(|t| t.0).max().unwrap(),
+        tiles.iter().map(|t| t.1).max().unwrap(),
+    );
+    ensure!(
+        tiles.iter().all(|t| t.0 >= 0 && t.1 >= 0) && max_x <= 1000 && max_y <= 1000,
+        "Coordinates out of range for the reference"
+    );
+
+    let on_edge = |(x, y): (i64, i64)| {
+        edges.iter().any(|(a, b)| {
+            (a.0.min(b.0)..=a.0.max(b.0)).contains(&x) && (a.1.min(b.1)..=a.1.max(b.1)).contains(&y)
+        })
+    };
+    // Casts a ray to the right and counts the vertical edges it crosses.
+    let inside = |(x, y): (i64, i64)| {
+        edges
+            .iter()
+            .filter(|(a, b)| a.0 == b.0 && a.0 > x && a.1.min(b.1) <= y && y < a.1.max(b.1))
+            .count()
+            % 2
+            == 1
+    };
+    let colored = (0..=max_y)
+        .map(|y| {
+            (0..=max_x)
+                .map(|x| on_edge((x, y)) || inside((x, y)))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let rectangles = tiles
+        .iter()
+        .tuple_combinations()
+        .map(|(a, b)| (a.0.min(b.0)..=a.0.max(b.0), a.1.min(b.1)..=a.1.max(b.1)));
+    let area = |(xs, ys): &(std::ops::RangeInclusive<i64>, std::ops::RangeInclusive<i64>)| {
+        (xs.end() - xs.start() + 1) * (ys.end() - ys.start() + 1)
+    };
+    let largest = rectangles.clone().map(|r| area(&r)).max().unwrap();
+    let largest_colored = rectangles
+        .filter(|(xs, ys)| {
+            ys.clone()
+                .all(|y| xs.clone().all(|x| colored[y as usize][x as usize]))
+        })
+        .map(|r| area(&r))
+        .max()
+        .unwrap();
+    Ok(answers(largest, largest_colored))
+}
+
+/// Tries every combination of button presses.
+pub fn day10(input: &str) -> Result<[String; 2]> {
+    let (mut fewest_toggles, mut fewest_presses) = (0, 0);
+    for line in input.lines() {
+        let mut parts = line.split(' ');
+        let lights = parts
+            .next()
+            .and_then(|lights| lights.strip_prefix('[')?.strip_suffix(']'))
+            .context("Lights missing")?
+            .chars()
+            .map(|c| c == '#')
+            .collect::<Vec<_>>();
+        let joltages = parts
+            .next_back()
+            .and_then(|joltages| joltages.strip_prefix('{')?.strip_suffix('}'))
+            .context("Joltages missing")?
+            .split(',')
+            .map(|n| n.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let buttons = parts
+            .map(|button| {
+                button
+                    .strip_prefix('(')
+                    .and_then(|button| button.strip_suffix(')'))
+                    .context("Button without parentheses")?
+                    .split(',')
+                    .map(|n| Ok(n.parse::<usize>()?))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(buttons.len() <= 16, "Too many buttons for the reference");
+        ensure!(
+            lights.len() == joltages.len()
+                && buttons.iter().flatten().all(|&light| light < lights.len()),
+            "Buttons, lights and joltages do not match"
+        );
+
+        // Pressing a button twice cancels out, so every subset of buttons is tried once.
+        fewest_toggles += (0..(1u32 << buttons.len()))
+            .filter(|pressed| {
+                let mut on = vec![false; lights.len()];
+                for (_, button) in buttons
+                    .iter()
+                    .enumerate()
+                    .filter(|(b, _)| pressed & (1 << b) != 0)
+                {
+                    button.iter().for_each(|&light| on[light] ^= true);
+                }
+                on == lights
+            })
+            .map(u32::count_ones)
+            .min()
+            .context("Lights can not be reached")?;
+        fewest_presses += fewest_joltage_presses(&buttons, joltages, &mut HashMap::new())
+            .context("Joltages can not be reached")?;
+    }
+    Ok(answers(fewest_toggles, fewest_presses))
+}
+
+/// Tries every number of presses of the first button that does not overshoot, then continues
+/// with the remaining buttons.
+fn fewest_joltage_presses(
+    buttons: &[Vec<usize>],
+    remaining: Vec<u64>,
+    cache: &mut HashMap<(usize, Vec<u64>), Option<u64>>,
+) -> Option<u64> {
+    let Some((button, rest)) = buttons.split_first() else {
+        return remaining.iter().all(|&j| j == 0).then_some(0);
+    };
+    let key = (rest.len(), remaining);
+    if let Some(&known) = cache.get(&key) {
+        return known;
+    }
+    let remaining = &key.1;
+    let most = button
+        .iter()
+        .map(|&light| remaining[light])
+        .min()
+        .unwrap_or(0);
+    let fewest = (0..=most)
+        .filter_map(|presses| {
+            let mut next = remaining.clone();
+            button.iter().for_each(|&light| next[light] -= presses);
+            fewest_joltage_presses(rest, next, cache).map(|count| count + presses)
+        })
+        .min();
+    cache.insert(key, fewest);
+    fewest
+}
+
+/// Walks every path through the device graph.
+pub fn day11(input: &str) -> Result<[String; 2]> {
+    let mut outputs = HashMap::new();
+    for line in input.lines() {
+        let (device, connected) = line.split_once(": ").context("Device without outputs")?;
+        outputs.insert(device, connected.split_whitespace().collect::<Vec<_>>());
+    }
+    // Counts the paths from `device` to `out`, split by whether they pass `dac` and `fft`.
+    fn walk<'a>(
+        device: &'a str,
+        outputs: &HashMap<&'a str, Vec<&'a str>>,
+        path: &mut Vec<&'a str>,
+        counts: &mut [u64; 2],
+    ) -> Result<()> {
+        ensure!(!path.contains(&device), "Cycle through {device}");
+        if device == "out" {
+            counts[0] += 1;
+            if path.contains(&"dac") && path.contains(&"fft") {
+                counts[1] += 1;
+            }
+            return Ok(());
+        }
+        path.push(device);
+        for next in outputs.get(device).into_iter().flatten() {
+            walk(next, outputs, path, counts)?;
+        }
+        path.pop();
+        Ok(())
+    }
+    let (mut from_you, mut from_svr) = ([0; 2], [0; 2]);
+    walk("you", &outputs, &mut Vec::new(), &mut from_you)?;
+    walk("svr", &outputs, &mut Vec::new(), &mut from_svr)?;
+    Ok(answers(from_you[0], from_svr[1]))
+}
+
+/// All distinct rotations and flips of a shape, as cell offsets.
+fn orientations(shape: &[(usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+    let normalize = |cells: Vec<(isize, isize)>| {
+        let min_x = cells.iter().map(|c| c.0).min().unwrap();
+        let min_y = cells.iter().map(|c| c.1).min().unwrap();
+        cells
+            .into_iter()
+            .map(|(x, y)| ((x - min_x) as usize, (y - min_y) as usize))
+            .sorted()
+            .collect::<Vec<_>>()
+    };
+    let mut cells = shape
+        .iter()
+        .map(|&(x, y)| (x as isize, y as isize))
+        .collect::<Vec<_>>();
+    let mut all = Vec::new();
+    for _ in 0..4 {
+        cells = cells.iter().map(|&(x, y)| (-y, x)).collect();
+        all.push(normalize(cells.clone()));
+        all.push(normalize(cells.iter().map(|&(x, y)| (-x, y)).collect()));
+    }
+    all.into_iter().unique().collect()
+}
+
+/// Tries every placement of every present, one present after another, as long as the remaining
+/// presents have no more cells than are free.
+fn fits(region: &mut Vec<Vec<bool>>, presents: &[&[Vec<(usize, usize)>]], free: usize) -> bool {
+    if presents
+        .iter()
+        .map(|present| present[0].len())
+        .sum::<usize>()
+        > free
+    {
+        return false;
+    }
+    let Some((orientations, rest)) = presents.split_first() else {
+        return true;
+    };
+    let (height, width) = (region.len(), region[0].len());
+    for cells in orientations.iter() {
+        for (dy, dx) in (0..height).cartesian_product(0..width) {
+            let placed = cells
+                .iter()
+                .map(|&(x, y)| (x + dx, y + dy))
+                .collect::<Vec<_>>();
+            if placed
+                .iter()
+                .all(|&(x, y)| x < width && y < height && !region[y][x])
+            {
+                placed.iter().for_each(|&(x, y)| region[y][x] = true);
+                let fit = fits(region, rest, free - cells.len());
+                placed.iter().for_each(|&(x, y)| region[y][x] = false);
+                if fit {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Actually packs the presents into every region.
+pub fn day12(input: &str) -> Result<[String; 2]> {
+    let sections = input.split("\n\n").collect::<Vec<_>>();
+    let (regions, shapes) = sections.split_last().context("Empty input")?;
+    let shapes = shapes
+        .iter()
+        .map(|shape| {
+            let cells = shape
+                .lines()
+                .skip(1)
+                .enumerate()
+                .flat_map(|(y, row)| {
+                    row.chars()
+                        .enumerate()
+                        .filter(|(_, c)| *c == '#')
+                        .map(move |(x, _)| (x, y))
+                })
+                .collect::<Vec<_>>();
+            ensure!(!cells.is_empty(), "Empty shape");
+            Ok(orientations(&cells))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut fitting = 0;
+    for region in regions.lines() {
+        let (size, counts) = region.split_once(": ").context("No : in region")?;
+        let (width, height) = size.split_once('x').context("No dimension separator")?;
+        let (width, height) = (width.parse::<usize>()?, height.parse::<usize>()?);
+        let counts = counts
+            .split(' ')
+            .map(|n| n.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()?;
+        ensure!(counts.len() <= shapes.len(), "Unknown shape");
+        ensure!(width * height <= 64, "Region too large for the reference");
+        let presents = counts
+            .iter()
+            .enumerate()
+            .flat_map(|(shape, &count)| std::iter::repeat_n(shapes[shape].as_slice(), count))
+            .collect::<Vec<_>>();
+        ensure!(presents.len() <= 6, "Too many presents for the reference");
+        if width > 0
+            && height > 0
+            && fits(
+                &mut vec![vec![false; width]; height],
+                &presents,
+                width * height,
+            )
+        {
+            fitting += 1;
+        }
+    }
+    // The last day only has a single puzzle.
+    Ok(answers(fitting, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        assert_eq!(
+            day1("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82").unwrap(),
+            answers(3, 6)
+        );
+        assert_eq!(
+            day7(".......S.......\n...............\n.......^.......\n...............\n......^.^......")
+                .unwrap(),
+            answers(3, 4)
+        );
+        assert_eq!(
+            day9("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3").unwrap(),
+            answers(50, 24)
+        );
+        assert_eq!(
+            day11("svr: aaa bbb\naaa: fft\nfft: ccc\nbbb: tty\ntty: ccc\nccc: ddd eee\nddd: hub\nhub: fff\neee: dac\ndac: fff\nfff: ggg hhh\nggg: out\nhhh: out\nyou: out")
+                .unwrap(),
+            answers(1, 2)
+        );
+    }
+
+    #[test]
+    fn packs_presents() {
+        let shapes = "0:\n###\n##.\n##.\n\n1:\n###\n##.\n.##\n\n2:\n.##\n###\n##.\n\n3:\n##.\n###\n##.\n\n4:\n###\n#..\n###\n\n5:\n###\n.#.\n###";
+        assert_eq!(
+            day12(&format!("{shapes}\n\n4x4: 0 0 0 0 2 0\n3x3: 0 0 0 0 2 0")).unwrap(),
+            answers(1, 0)
+        );
+    }
+}
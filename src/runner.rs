@@ -0,0 +1,92 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+
+use crate::solver;
+
+/// Which parts of a day should be solved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Parts {
+    One,
+    Two,
+    All,
+}
+
+impl Parts {
+    fn includes(self, part: u8) -> bool {
+        matches!(
+            (self, part),
+            (Parts::All, _) | (Parts::One, 1) | (Parts::Two, 2)
+        )
+    }
+}
+
+impl FromStr for Parts {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Parts::One),
+            "2" => Ok(Parts::Two),
+            "all" => Ok(Parts::All),
+            _ => bail!("Unknown part {s:?}, expected 1, 2 or all"),
+        }
+    }
+}
+
+/// The answer of a single part together with the time it took to compute it.
+#[derive(Debug)]
+pub struct Answer {
+    pub part: u8,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+/// Everything produced by solving one day: the time spent in the generator and the answers.
+#[derive(Debug)]
+pub struct Solution {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub answers: Vec<Answer>,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+/// Parses `input` with the generator of `day` and solves the requested `parts`.
+pub fn solve(day: u8, parts: Parts, input: &str) -> Result<Solution> {
+    let puzzle = solver::get(day).with_context(|| format!("No solution for day {day}"))?;
+
+    let (parsed, parse_time) = timed(|| puzzle.parse(input));
+    let parsed = parsed?;
+
+    let mut answers = Vec::new();
+    if parts.includes(1) {
+        let (value, elapsed) = timed(|| puzzle.part1(&parsed));
+        answers.push(Answer {
+            part: 1,
+            value: value?,
+            elapsed,
+        });
+    }
+    if parts.includes(2) {
+        let (value, elapsed) = timed(|| puzzle.part2(&parsed));
+        answers.push(Answer {
+            part: 2,
+            value: value?,
+            elapsed,
+        });
+    }
+
+    Ok(Solution {
+        day,
+        parse_time,
+        answers,
+    })
+}
@@ -0,0 +1,131 @@
+use std::{any::Any, fmt::Display, marker::PhantomData};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    day1::Day1, day2::Day2, day3::Day3, day4::Day4, day5::Day5, day6::Day6, day7::Day7, day8::Day8,
+    day9::Day9, day10::Day10, day11::Day11, day12::Day12, error::ParseError,
+};
+
+/// A puzzle solution for a single day.
+///
+/// Implementations only forward to the `parse`, `part1` and `part2` functions of their day module,
+/// so the `#[aoc_generator]` and `#[aoc]` attributes keep working on the very same code.
+pub trait Solver {
+    const DAY: u8;
+
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+/// The parsed input of a [`Puzzle`], only usable with the puzzle that produced it.
+pub struct Parsed {
+    day: u8,
+    input: Box<dyn Any>,
+}
+
+/// Type erased [`Solver`], so solutions with different input types can live in one registry.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u8;
+    fn parse(&self, input: &str) -> Result<Parsed>;
+    fn part1(&self, input: &Parsed) -> Result<String>;
+    fn part2(&self, input: &Parsed) -> Result<String>;
+}
+
+struct Registered<S>(PhantomData<fn() -> S>);
+
+impl<S: Solver> Registered<S>
+where
+    S::Input: 'static,
+{
+    fn input<'a>(&self, parsed: &'a Parsed) -> Result<&'a S::Input> {
+        parsed
+            .input
+            .downcast_ref()
+            .filter(|_| parsed.day == S::DAY)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Input parsed for day {} can not be solved by day {}",
+                    parsed.day,
+                    S::DAY
+                )
+            })
+    }
+}
+
+impl<S: Solver> Puzzle for Registered<S>
+where
+    S::Input: 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed> {
+        Ok(Parsed {
+            day: S::DAY,
+            input: Box::new(S::parse(input)?),
+        })
+    }
+
+    fn part1(&self, input: &Parsed) -> Result<String> {
+        Ok(S::part1(self.input(input)?).to_string())
+    }
+
+    fn part2(&self, input: &Parsed) -> Result<String> {
+        Ok(S::part2(self.input(input)?).to_string())
+    }
+}
+
+static REGISTRY: [&dyn Puzzle; 12] = [
+    &Registered::<Day1>(PhantomData),
+    &Registered::<Day2>(PhantomData),
+    &Registered::<Day3>(PhantomData),
+    &Registered::<Day4>(PhantomData),
+    &Registered::<Day5>(PhantomData),
+    &Registered::<Day6>(PhantomData),
+    &Registered::<Day7>(PhantomData),
+    &Registered::<Day8>(PhantomData),
+    &Registered::<Day9>(PhantomData),
+    &Registered::<Day10>(PhantomData),
+    &Registered::<Day11>(PhantomData),
+    &Registered::<Day12>(PhantomData),
+];
+
+/// All registered puzzles, ordered by day.
+pub fn puzzles() -> impl Iterator<Item = &'static dyn Puzzle> {
+    REGISTRY.iter().copied()
+}
+
+/// The days that have a registered puzzle.
+pub fn days() -> impl Iterator<Item = u8> {
+    puzzles().map(|puzzle| puzzle.day())
+}
+
+/// Looks up the puzzle of `day`.
+pub fn get(day: u8) -> Option<&'static dyn Puzzle> {
+    puzzles().find(|puzzle| puzzle.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_keyed_by_day() {
+        assert_eq!(days().collect::<Vec<_>>(), (1..=12).collect::<Vec<_>>());
+        assert!(get(13).is_none());
+    }
+
+    #[test]
+    fn parsed_input_is_bound_to_its_day() {
+        let parsed = get(1).unwrap().parse("R50").unwrap();
+        assert_eq!(get(1).unwrap().part1(&parsed).unwrap(), "1");
+        assert!(get(2).unwrap().part1(&parsed).is_err());
+    }
+}
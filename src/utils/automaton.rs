@@ -0,0 +1,375 @@
+//! Two-dimensional cellular automata on grids of live and dead cells.
+
+use crate::utils::grid::{DIAGONAL, Grid, ORTHOGONAL, Pos};
+
+/// The cells whose states decide the next state of a cell, as offsets from it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// The 8 orthogonally or diagonally adjacent cells.
+    Moore,
+    /// The 4 orthogonally adjacent cells.
+    VonNeumann,
+    Custom(Vec<(isize, isize)>),
+}
+
+impl Neighbourhood {
+    fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            Neighbourhood::Moore => ORTHOGONAL.into_iter().chain(DIAGONAL).collect(),
+            Neighbourhood::VonNeumann => ORTHOGONAL.to_vec(),
+            Neighbourhood::Custom(offsets) => offsets.clone(),
+        }
+    }
+}
+
+/// What lies beyond the edges of the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Edges {
+    /// Dead cells.
+    Bounded,
+    /// The opposite edge, as on a torus.
+    Toroidal,
+}
+
+/// When updated cells become visible to their neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Update {
+    /// After the whole generation, so every cell sees the previous one.
+    Synchronous,
+    /// Right away, updating the cells one by one in row-major order.
+    Asynchronous,
+}
+
+/// A set of numbers of live neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Counts(u64);
+
+impl Counts {
+    pub const NONE: Counts = Counts(0);
+
+    /// The counts from `min` to `max`, both inclusive.
+    pub const fn range(min: usize, max: usize) -> Self {
+        let mut counts = 0;
+        let mut count = min;
+        while count <= max && count < 64 {
+            counts |= 1 << count;
+            count += 1;
+        }
+        Counts(counts)
+    }
+
+    pub const fn at_least(min: usize) -> Self {
+        Self::range(min, 63)
+    }
+
+    pub const fn below(max: usize) -> Self {
+        if max == 0 {
+            Self::NONE
+        } else {
+            Self::range(0, max - 1)
+        }
+    }
+
+    pub const fn complement(self) -> Self {
+        Counts(!self.0)
+    }
+
+    pub fn contains(self, count: usize) -> bool {
+        count < 64 && self.0 & (1 << count) != 0
+    }
+}
+
+/// How the number of live neighbours decides the next state of a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// Dead cells with this many live neighbours come alive.
+    pub birth: Counts,
+    /// Live cells with this many live neighbours stay alive, all others are removed.
+    pub survival: Counts,
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23.
+    pub const LIFE: Rule = Rule {
+        birth: Counts::range(3, 3),
+        survival: Counts::range(2, 3),
+    };
+
+    /// Removes live cells with `removal` many live neighbours, without any births.
+    pub const fn removing(removal: Counts) -> Self {
+        Rule {
+            birth: Counts::NONE,
+            survival: removal.complement(),
+        }
+    }
+
+    fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survival.contains(neighbours)
+        } else {
+            self.birth.contains(neighbours)
+        }
+    }
+}
+
+/// A cellular automaton, applying `rule` to the live cells within the neighbourhood of every
+/// cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Automaton {
+    pub neighbourhood: Neighbourhood,
+    pub rule: Rule,
+    pub edges: Edges,
+    pub update: Update,
+}
+
+/// One generation, showing the cells before the changed ones flip.
+pub struct Generation<'a> {
+    pub number: usize,
+    pub grid: &'a Grid<bool>,
+    pub changed: &'a [Pos],
+}
+
+/// How running an automaton ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Nothing changed any more after this many generations.
+    Fixed { generations: usize },
+    /// The grid after `start` generations came back every `period` generations.
+    Cycle { start: usize, period: usize },
+}
+
+impl Automaton {
+    /// An automaton on a bounded grid with synchronous updates.
+    pub fn new(neighbourhood: Neighbourhood, rule: Rule) -> Self {
+        Self {
+            neighbourhood,
+            rule,
+            edges: Edges::Bounded,
+            update: Update::Synchronous,
+        }
+    }
+
+    fn neighbour(&self, grid: &Grid<bool>, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let (width, height) = (grid.width(), grid.height());
+        match self.edges {
+            Edges::Bounded => Some((
+                x.checked_add_signed(dx).filter(|&x| x < width)?,
+                y.checked_add_signed(dy).filter(|&y| y < height)?,
+            )),
+            Edges::Toroidal => Some((
+                (x as isize + dx).rem_euclid(width as isize) as usize,
+                (y as isize + dy).rem_euclid(height as isize) as usize,
+            )),
+        }
+    }
+
+    /// The number of live cells within the neighbourhood of `pos`.
+    pub fn live_neighbours(&self, grid: &Grid<bool>, pos: Pos) -> usize {
+        self.count(&self.neighbourhood.offsets(), grid, pos)
+    }
+
+    fn count(&self, offsets: &[(isize, isize)], grid: &Grid<bool>, pos: Pos) -> usize {
+        offsets
+            .iter()
+            .filter_map(|&offset| self.neighbour(grid, pos, offset))
+            .filter(|&neighbour| grid[neighbour])
+            .count()
+    }
+
+    /// The cells the next synchronous generation flips, in row-major order.
+    pub fn changes(&self, grid: &Grid<bool>) -> Vec<Pos> {
+        let offsets = self.neighbourhood.offsets();
+        grid.iter()
+            .filter(|&(pos, &alive)| {
+                self.rule.next(alive, self.count(&offsets, grid, pos)) != alive
+            })
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    /// Advances `grid` by one generation and returns the cells that flipped.
+    pub fn step(&self, grid: &mut Grid<bool>) -> Vec<Pos> {
+        match self.update {
+            Update::Synchronous => {
+                let changed = self.changes(grid);
+                for &pos in &changed {
+                    grid[pos] = !grid[pos];
+                }
+                changed
+            }
+            Update::Asynchronous => {
+                let offsets = self.neighbourhood.offsets();
+                grid.positions()
+                    .filter(|&pos| {
+                        let alive = grid[pos];
+                        grid[pos] = self.rule.next(alive, self.count(&offsets, grid, pos));
+                        grid[pos] != alive
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Runs the automaton on `grid` until it reaches a fixed point or repeats an earlier grid,
+    /// reporting every generation that changes something to `observer`.
+    ///
+    /// Returns the final grid, which for a cycle is the first one to repeat. Cycles are found
+    /// with Brent's algorithm, keeping only two grids, and not looked for at all if the rule has
+    /// no births, as live cells then only ever disappear.
+    pub fn run(
+        &self,
+        grid: &Grid<bool>,
+        mut observer: impl FnMut(Generation<'_>),
+    ) -> (Grid<bool>, Outcome) {
+        let cycles = self.rule.birth != Counts::NONE;
+        let mut hare = grid.clone();
+        // The tortoise waits at the start of ever longer stretches for the hare to come back.
+        let mut tortoise = grid.clone();
+        let (mut stretch, mut period) = (1, 0);
+        for number in 1.. {
+            let before = hare.clone();
+            let changed = self.step(&mut hare);
+            if changed.is_empty() {
+                return (
+                    hare,
+                    Outcome::Fixed {
+                        generations: number - 1,
+                    },
+                );
+            }
+            observer(Generation {
+                number,
+                grid: &before,
+                changed: &changed,
+            });
+            if !cycles {
+                continue;
+            }
+            period += 1;
+            if hare == tortoise {
+                return self.cycle(grid, period);
+            }
+            if period == stretch {
+                tortoise = hare.clone();
+                stretch *= 2;
+                period = 0;
+            }
+        }
+        unreachable!("Generations never run out.")
+    }
+
+    /// Finds the first grid from `grid` on that comes back after `period` generations.
+    fn cycle(&self, grid: &Grid<bool>, period: usize) -> (Grid<bool>, Outcome) {
+        let mut first = grid.clone();
+        let mut later = grid.clone();
+        for _ in 0..period {
+            self.step(&mut later);
+        }
+        let mut start = 0;
+        while first != later {
+            self.step(&mut first);
+            self.step(&mut later);
+            start += 1;
+        }
+        (first, Outcome::Cycle { start, period })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(source: &str) -> Grid<bool> {
+        Grid::parse(0, source, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn life_oscillates_and_settles() {
+        let life = Automaton::new(Neighbourhood::Moore, Rule::LIFE);
+        let blinker = grid(".....\n..#..\n..#..\n..#..\n.....");
+        let mut generations = 0;
+        let (last, outcome) = life.run(&blinker, |_| generations += 1);
+        assert_eq!(
+            outcome,
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            }
+        );
+        assert_eq!(last, blinker);
+        assert_eq!(generations, 3);
+
+        // The lonely cell dies before the blinker starts to repeat.
+        let lonely = grid("#......\n.......\n...#...\n...#...\n...#...\n.......\n.......");
+        let (last, outcome) = life.run(&lonely, |_| {});
+        assert_eq!(
+            outcome,
+            Outcome::Cycle {
+                start: 1,
+                period: 2
+            }
+        );
+        assert_eq!(
+            last.find_all(|&alive| alive).collect::<Vec<_>>(),
+            [(2, 3), (3, 3), (4, 3)]
+        );
+
+        let block = grid("....\n.##.\n.##.\n....");
+        assert_eq!(
+            life.run(&block, |_| {}),
+            (block, Outcome::Fixed { generations: 0 })
+        );
+    }
+
+    #[test]
+    fn glider_wraps_around_a_torus() {
+        let life = Automaton {
+            edges: Edges::Toroidal,
+            ..Automaton::new(Neighbourhood::Moore, Rule::LIFE)
+        };
+        let glider = grid(".#...\n..#..\n###..\n.....\n.....");
+        // The glider moves one cell diagonally every 4 generations.
+        let (_, outcome) = life.run(&glider, |_| {});
+        assert_eq!(
+            outcome,
+            Outcome::Cycle {
+                start: 0,
+                period: 20
+            }
+        );
+    }
+
+    #[test]
+    fn neighbourhoods_and_updates() {
+        let cross = grid("#.#\n.#.\n#.#");
+        let moore = Automaton::new(Neighbourhood::Moore, Rule::LIFE);
+        assert_eq!(moore.live_neighbours(&cross, (1, 1)), 4);
+        let von_neumann = Automaton::new(Neighbourhood::VonNeumann, Rule::LIFE);
+        assert_eq!(von_neumann.live_neighbours(&cross, (1, 1)), 0);
+        let knight = Automaton::new(Neighbourhood::Custom(vec![(1, 2), (2, 1)]), Rule::LIFE);
+        assert_eq!(knight.live_neighbours(&cross, (0, 0)), 0);
+        assert_eq!(knight.live_neighbours(&cross, (0, 1)), 1);
+
+        // Removing cells with fewer than 2 neighbours: the updated corner makes its neighbours
+        // lonely too when they see it right away.
+        let line = grid("###.#");
+        let removal = Rule::removing(Counts::below(2));
+        let mut synchronous = line.clone();
+        let automaton = Automaton::new(Neighbourhood::VonNeumann, removal);
+        assert_eq!(automaton.step(&mut synchronous), [(0, 0), (2, 0), (4, 0)]);
+        let mut asynchronous = line.clone();
+        let automaton = Automaton {
+            update: Update::Asynchronous,
+            ..automaton
+        };
+        assert_eq!(
+            automaton.step(&mut asynchronous),
+            [(0, 0), (1, 0), (2, 0), (4, 0)]
+        );
+    }
+}
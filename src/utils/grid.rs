@@ -0,0 +1,311 @@
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+
+/// A position within a grid, as `(x, y)` with `y` growing downwards.
+pub type Pos = (usize, usize);
+
+/// The offsets of the orthogonally adjacent positions, clockwise from up.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+/// The offsets of the diagonally adjacent positions, clockwise from up right.
+pub const DIAGONAL: [(isize, isize); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// A dense, rectangular grid of cells stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row-major order.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "A {width}x{height} grid needs {} cells.",
+            width * height
+        );
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Creates a grid with every cell set to `value`.
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(width, height, vec![value; width * height])
+    }
+
+    fn parse_rows(
+        day: u8,
+        source: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Vec<(&str, Vec<T>)>, ParseError> {
+        source
+            .lines()
+            .map(|line| {
+                let row = line
+                    .char_indices()
+                    .map(|(pos, c)| {
+                        cell(c).ok_or_else(|| {
+                            let token = &line[pos..(pos + c.len_utf8())];
+                            ParseError::new(day, source, token, "Unexpected character in grid.")
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((line, row))
+            })
+            .collect()
+    }
+
+    /// Parses one cell per character, one row per line.
+    ///
+    /// Errors point into `source` for characters `cell` rejects and for rows whose length differs
+    /// from the first one.
+    pub fn parse(
+        day: u8,
+        source: &str,
+        cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let rows = Self::parse_rows(day, source, cell)?;
+        let width = rows.first().map_or(0, |(_, row)| row.len());
+        if let Some((line, _)) = rows.iter().find(|(_, row)| row.len() != width) {
+            return Err(ParseError::new(
+                day,
+                source,
+                line,
+                "Row length differs from the first row.",
+            ));
+        }
+        let height = rows.len();
+        Ok(Self::new(
+            width,
+            height,
+            rows.into_iter().flat_map(|(_, row)| row).collect(),
+        ))
+    }
+
+    /// Parses like [`Grid::parse`], but pads rows shorter than the longest one with `padding`,
+    /// for inputs whose trailing blanks were trimmed.
+    pub fn parse_ragged(
+        day: u8,
+        source: &str,
+        padding: T,
+        cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError>
+    where
+        T: Clone,
+    {
+        let rows = Self::parse_rows(day, source, cell)?;
+        let width = rows.iter().map(|(_, row)| row.len()).max().unwrap_or(0);
+        let height = rows.len();
+        Ok(Self::new(
+            width,
+            height,
+            rows.into_iter()
+                .flat_map(|(_, mut row)| {
+                    row.resize(width, padding.clone());
+                    row
+                })
+                .collect(),
+        ))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Pos) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Iterates over all positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterates over all cells together with their positions in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(x < self.width, "Column {x} lies outside of the grid.");
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    fn offsets<I: Iterator<Item = (isize, isize)>>(
+        &self,
+        (x, y): Pos,
+        offsets: I,
+    ) -> impl Iterator<Item = Pos> + use<T, I> {
+        let (width, height) = (self.width, self.height);
+        offsets.filter_map(move |(dx, dy)| {
+            let x = x.checked_add_signed(dx).filter(|&x| x < width)?;
+            let y = y.checked_add_signed(dy).filter(|&y| y < height)?;
+            Some((x, y))
+        })
+    }
+
+    /// The up to 4 orthogonally adjacent positions within the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + use<T> {
+        self.offsets(pos, ORTHOGONAL.into_iter())
+    }
+
+    /// The up to 8 orthogonally or diagonally adjacent positions within the grid.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + use<T> {
+        self.offsets(pos, ORTHOGONAL.into_iter().chain(DIAGONAL))
+    }
+
+    /// The first position in row-major order whose cell matches `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find_map(|(pos, cell)| predicate(cell).then_some(pos))
+    }
+
+    /// All positions in row-major order whose cells match `predicate`.
+    pub fn find_all<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Pos> + 'a {
+        self.iter()
+            .filter_map(move |(pos, cell)| predicate(cell).then_some(pos))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    /// Mirrors the grid along its main diagonal, turning rows into columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(
+            self.height,
+            self.width,
+            (0..self.width)
+                .flat_map(|x| self.column(x).cloned())
+                .collect(),
+        )
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotate(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(
+            self.height,
+            self.width,
+            (0..self.width)
+                .flat_map(|x| self.column(x).rev().cloned())
+                .collect(),
+        )
+    }
+
+    /// Renders one character per cell and one line per row.
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        self.rows()
+            .take(self.height)
+            .map(|row| row.iter().map(&mut cell).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} lies outside of the grid."))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} lies outside of the grid."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(input: &str) -> Grid<char> {
+        Grid::parse(0, input, Some).unwrap()
+    }
+
+    #[test]
+    fn parses_and_renders() {
+        let grid = Grid::parse(0, "#..\n.#.", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid[(1, 1)] && !grid[(2, 1)]);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.find_all(|&c| c).collect::<Vec<_>>(), [(0, 0), (1, 1)]);
+        assert_eq!(grid.render(|&c| if c { '#' } else { '.' }), "#..\n.#.");
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = Grid::parse(4, "@.\n.x", |c| (c != 'x').then_some(c)).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+        let err = Grid::parse(4, "@.\n.", Some).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn pads_ragged_rows() {
+        let grid = Grid::parse_ragged(0, "ab\nc", ' ', Some).unwrap();
+        assert_eq!(grid.render(|&c| c), "ab\nc ");
+    }
+
+    #[test]
+    fn neighbours_stay_within_bounds() {
+        let grid = chars("abc\ndef\nghi");
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbours4((2, 1))
+                .map(|pos| grid[pos])
+                .collect::<String>(),
+            "cie"
+        );
+    }
+
+    #[test]
+    fn transposes_and_rotates() {
+        let grid = chars("abc\ndef");
+        assert_eq!(grid.transpose().render(|&c| c), "ad\nbe\ncf");
+        assert_eq!(grid.rotate().render(|&c| c), "da\neb\nfc");
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+        assert_eq!(grid.find(|&c| c == 'e'), Some((1, 1)));
+    }
+}
@@ -0,0 +1,224 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
+use crate::error::ParseError;
+
+/// Parsing helpers that report errors at their position within `input`.
+///
+/// Every text handed to the helpers has to be a slice of `input`, so that errors can point at
+/// the offending token.
+#[derive(Clone, Copy, Debug)]
+pub struct Parser<'a> {
+    day: u8,
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(day: u8, input: &'a str) -> Self {
+        Self { day, input }
+    }
+
+    pub fn error(&self, token: &str, reason: impl Into<String>) -> ParseError {
+        ParseError::new(self.day, self.input, token, reason)
+    }
+
+    /// An error pointing right behind `text`, for something missing at its end.
+    pub fn missing(&self, text: &str, reason: impl Into<String>) -> ParseError {
+        self.error(&text[text.len()..], reason)
+    }
+
+    pub fn number<T: FromStr>(&self, token: &str, reason: &str) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error(token, reason))
+    }
+
+    /// Parses the numbers in `text` separated by `separator`.
+    ///
+    /// A trailing separator is ignored. A separator of only whitespace splits at runs of
+    /// whitespace instead, which also ignores leading and trailing whitespace.
+    pub fn numbers<T: FromStr>(
+        &self,
+        text: &'a str,
+        separator: &str,
+        reason: &str,
+    ) -> Result<Vec<T>, ParseError> {
+        let tokens = if separator.trim().is_empty() {
+            text.split_whitespace().collect::<Vec<_>>()
+        } else {
+            text.strip_suffix(separator)
+                .unwrap_or(text)
+                .split(separator)
+                .collect()
+        };
+        tokens
+            .into_iter()
+            .map(|token| self.number(token, reason))
+            .collect()
+    }
+
+    /// The digits of `token` in the given radix.
+    pub fn digits(&self, token: &str, radix: u32) -> Result<Vec<u32>, ParseError> {
+        token
+            .char_indices()
+            .map(|(pos, c)| {
+                c.to_digit(radix)
+                    .ok_or_else(|| self.error(&token[pos..(pos + c.len_utf8())], "Not a digit."))
+            })
+            .collect()
+    }
+
+    /// Splits `text` at the first `separator`.
+    pub fn split(
+        &self,
+        text: &'a str,
+        separator: &str,
+        reason: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(separator)
+            .ok_or_else(|| self.missing(text, reason))
+    }
+
+    /// Removes `open` and `close` around `text`.
+    pub fn enclosed(&self, text: &'a str, open: char, close: char) -> Result<&'a str, ParseError> {
+        text.strip_prefix(open)
+            .ok_or_else(|| self.error(text, format!("Missing {open:?}.")))?
+            .strip_suffix(close)
+            .ok_or_else(|| self.missing(text, format!("Missing {close:?}.")))
+    }
+
+    /// Parses an inclusive range written as `a-b`, where `a` may be negative.
+    pub fn range<T: FromStr>(&self, text: &'a str) -> Result<RangeInclusive<T>, ParseError> {
+        self.range_with(text, |n| n.parse().ok())
+    }
+
+    /// Parses an inclusive range like [`Parser::range`], with its ends parsed by `number`.
+    pub fn range_with<T>(
+        &self,
+        text: &'a str,
+        number: impl Fn(&str) -> Option<T>,
+    ) -> Result<RangeInclusive<T>, ParseError> {
+        let dash = text
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c == '-')
+            .map(|(pos, _)| pos)
+            .ok_or_else(|| self.missing(text, "Range without -."))?;
+        let (start, end) = (&text[..dash], &text[(dash + 1)..]);
+        let start =
+            number(start).ok_or_else(|| self.error(start, "Range start is not a number."))?;
+        let end = number(end).ok_or_else(|| self.error(end, "Range end is not a number."))?;
+        Ok(start..=end)
+    }
+
+    /// Parses exactly `N` coordinates separated by `separator`, e.g. `x,y` or `x,y,z`.
+    pub fn tuple<T: FromStr, const N: usize>(
+        &self,
+        text: &'a str,
+        separator: &str,
+    ) -> Result<[T; N], ParseError> {
+        let mut tokens = text.split(separator);
+        let coordinates = (0..N)
+            .map(|_| {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| self.missing(text, "Missing coordinate."))?;
+                self.number(token, "Coordinate is not a number.")
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(token) = tokens.next() {
+            return Err(self.error(token, "Too many coordinates."));
+        }
+        Ok(coordinates
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("Exactly N coordinates were parsed.")))
+    }
+
+    /// Splits `text` into at most `limit` sections between blank lines, the last one holding the
+    /// rest of `text`.
+    pub fn sections(&self, text: &'a str, limit: usize) -> impl Iterator<Item = &'a str> + use<'a> {
+        text.splitn(limit, "\n\n")
+    }
+
+    /// Parses a `key: values` line with whitespace separated values.
+    pub fn key_values<T: FromStr>(
+        &self,
+        line: &'a str,
+        reason: &str,
+    ) -> Result<(&'a str, Vec<T>), ParseError> {
+        let (key, values) = self.split(line, ": ", "Line without :.")?;
+        Ok((key, self.numbers(values, " ", reason)?))
+    }
+
+    /// Parses a `key: words` line with whitespace separated words, such as names.
+    pub fn key_words(&self, line: &'a str) -> Result<(&'a str, Vec<&'a str>), ParseError> {
+        let (key, words) = self.split(line, ": ", "Line without :.")?;
+        Ok((key, words.split_whitespace().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(err: ParseError) -> (usize, usize, String) {
+        (err.line, err.column, err.text)
+    }
+
+    #[test]
+    fn parses_numbers_and_ranges() {
+        let input = "1, -2, 3\n  4 5\n-7-12";
+        let parser = Parser::new(0, input);
+        let lines = input.lines().collect::<Vec<_>>();
+        assert_eq!(
+            parser.numbers::<i64>(lines[0], ", ", ""),
+            Ok(vec![1, -2, 3])
+        );
+        assert_eq!(parser.numbers::<u8>(lines[1], " ", ""), Ok(vec![4, 5]));
+        let trailing = "4\n5\n";
+        assert_eq!(
+            Parser::new(0, trailing).numbers::<u8>(trailing, "\n", ""),
+            Ok(vec![4, 5])
+        );
+        assert_eq!(parser.range::<i64>(lines[2]), Ok(-7..=12));
+        assert_eq!(
+            position(
+                parser
+                    .numbers::<u8>(lines[0], ", ", "Negative")
+                    .unwrap_err()
+            ),
+            (1, 4, "-2".to_owned())
+        );
+    }
+
+    #[test]
+    fn parses_tuples() {
+        let input = "1,2,3\n4,5";
+        let parser = Parser::new(0, input);
+        let (first, second) = input.split_once('\n').unwrap();
+        assert_eq!(parser.tuple::<i32, 3>(first, ","), Ok([1, 2, 3]));
+        assert_eq!(parser.tuple::<i32, 2>(second, ","), Ok([4, 5]));
+        assert_eq!(
+            position(parser.tuple::<i32, 2>(first, ",").unwrap_err()),
+            (1, 5, "3".to_owned())
+        );
+        assert_eq!(
+            position(parser.tuple::<i32, 3>(second, ",").unwrap_err()),
+            (2, 4, String::new())
+        );
+    }
+
+    #[test]
+    fn parses_sections_and_key_values() {
+        let input = "a: 1 2\nb: x\n\n4x4";
+        let parser = Parser::new(0, input);
+        let sections = parser.sections(input, 3).collect::<Vec<_>>();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(parser.sections("a\n\nb\n\nc", 2).last(), Some("b\n\nc"));
+        let lines = sections[0].lines().collect::<Vec<_>>();
+        assert_eq!(parser.key_values::<u8>(lines[0], ""), Ok(("a", vec![1, 2])));
+        assert_eq!(
+            position(parser.key_values::<u8>(lines[1], "").unwrap_err()),
+            (2, 4, "x".to_owned())
+        );
+        assert_eq!(parser.key_words(lines[1]), Ok(("b", vec!["x"])));
+        assert_eq!(parser.tuple::<u8, 2>(sections[1], "x"), Ok([4, 4]));
+    }
+}
@@ -0,0 +1,251 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+    fs,
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use toml::{Table, Value};
+
+use crate::{
+    runner::{self, Parts},
+    solver,
+};
+
+/// Known answers for the real puzzle inputs, keyed by day and part.
+///
+/// Stored as TOML with one table per day:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1234
+/// part2 = "6789"
+/// ```
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(u8, u8), String>);
+
+impl Answers {
+    pub fn parse(toml: &str) -> Result<Self> {
+        let table = toml
+            .parse::<Table>()
+            .context("Answers are not valid TOML")?;
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in table {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .with_context(|| format!("Expected [dayN] table, found [{day_key}]"))?;
+            let Value::Table(parts) = parts else {
+                bail!("Expected [{day_key}] to be a table");
+            };
+            for (part_key, answer) in parts {
+                let part = match part_key.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => bail!("Expected part1 or part2 in [{day_key}], found {part_key}"),
+                };
+                let answer = match answer {
+                    Value::String(answer) => answer,
+                    Value::Integer(answer) => answer.to_string(),
+                    Value::Float(answer) => answer.to_string(),
+                    answer => bail!("Unsupported answer {answer} for {day_key}.{part_key}"),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(Self(answers))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let toml = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&toml).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: String) {
+        self.0.insert((day, part), answer);
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut last_day = None;
+        for (&(day, part), answer) in &self.0 {
+            if last_day != Some(day) {
+                if last_day.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{day}]")?;
+                last_day = Some(day);
+            }
+            // Integers are written bare, everything else as a quoted string.
+            match answer.parse::<i64>() {
+                Ok(answer) => writeln!(f, "part{part} = {answer}")?,
+                Err(_) => writeln!(f, "part{part} = {}", Value::from(answer.as_str()))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Match,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// There is no recorded answer or no cached input; the reason says which.
+    Missing(String),
+    /// The solver could not produce an answer, e.g. because the input did not parse.
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub status: Status,
+}
+
+impl Check {
+    pub fn is_failure(&self) -> bool {
+        matches!(self.status, Status::Mismatch { .. } | Status::Failed(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {:>2} - part {}: ", self.day, self.part)?;
+        match &self.status {
+            Status::Match => write!(f, "match"),
+            Status::Mismatch { expected, actual } => {
+                write!(f, "MISMATCH (expected {expected}, got {actual})")
+            }
+            Status::Missing(reason) => write!(f, "missing ({reason})"),
+            Status::Failed(reason) => write!(f, "FAILED ({reason})"),
+        }
+    }
+}
+
+fn check(answers: &Answers, day: u8, part: u8, actual: Result<&str, &str>) -> Check {
+    let status = match (answers.get(day, part), actual) {
+        (_, Err(reason)) => Status::Failed(reason.to_owned()),
+        (None, Ok(_)) => Status::Missing("no answer recorded".to_owned()),
+        (Some(expected), Ok(actual)) if expected == actual => Status::Match,
+        (Some(expected), Ok(actual)) => Status::Mismatch {
+            expected: expected.to_owned(),
+            actual: actual.to_owned(),
+        },
+    };
+    Check { day, part, status }
+}
+
+/// Runs every registered solver on its cached input `day{N}.txt` in `input_dir` and compares the
+/// results with `answers`.
+///
+/// With `record`, answers of parts that have none yet are added to `answers`.
+pub fn verify(answers: &mut Answers, input_dir: &Path, record: bool) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for day in solver::days() {
+        let path = input_dir.join(format!("day{day}.txt"));
+        let Ok(input) = fs::read_to_string(&path) else {
+            checks.extend((1..=2).map(|part| Check {
+                day,
+                part,
+                status: Status::Missing(format!("no input at {}", path.display())),
+            }));
+            continue;
+        };
+        match runner::solve(day, Parts::All, input.trim_end_matches('\n')) {
+            Ok(solution) => {
+                for answer in solution.answers {
+                    if record && answers.get(day, answer.part).is_none() {
+                        answers.insert(day, answer.part, answer.value.clone());
+                    }
+                    checks.push(check(answers, day, answer.part, Ok(&answer.value)));
+                }
+            }
+            Err(err) => {
+                let reason = format!("{err:#}");
+                checks.extend((1..=2).map(|part| check(answers, day, part, Err(&reason))));
+            }
+        }
+    }
+    checks
+}
+
+/// Renders the checks as a report, one line per day and part, followed by a summary.
+pub fn report(checks: &[Check]) -> String {
+    let mut report = String::new();
+    for check in checks {
+        writeln!(report, "{check}").unwrap();
+    }
+    let count = |f: fn(&Status) -> bool| checks.iter().filter(|check| f(&check.status)).count();
+    write!(
+        report,
+        "{} matched, {} mismatched, {} missing, {} failed",
+        count(|status| matches!(status, Status::Match)),
+        count(|status| matches!(status, Status::Mismatch { .. })),
+        count(|status| matches!(status, Status::Missing(_))),
+        count(|status| matches!(status, Status::Failed(_))),
+    )
+    .unwrap();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_round_trip() {
+        let answers = Answers::parse(
+            "[day1]
+part1 = 3
+part2 = \"6\"
+
+[day10]
+part2 = 33.0
+",
+        )
+        .unwrap();
+        assert_eq!(answers.get(1, 1), Some("3"));
+        assert_eq!(answers.get(1, 2), Some("6"));
+        assert_eq!(answers.get(10, 2), Some("33"));
+        assert_eq!(answers.get(2, 1), None);
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert!(Answers::parse("[day1]\npart3 = 1").is_err());
+        assert!(Answers::parse("[first]\npart1 = 1").is_err());
+    }
+
+    #[test]
+    fn checks_against_recorded_answers() {
+        let mut answers = Answers::default();
+        answers.insert(1, 1, "3".to_owned());
+        answers.insert(1, 2, "7".to_owned());
+        assert_eq!(check(&answers, 1, 1, Ok("3")).status, Status::Match);
+        assert!(check(&answers, 1, 2, Ok("6")).is_failure());
+        assert!(matches!(
+            check(&answers, 2, 1, Ok("1")).status,
+            Status::Missing(_)
+        ));
+    }
+}
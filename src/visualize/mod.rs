@@ -0,0 +1,252 @@
+//! Pictures of the spatial puzzles, for finding wrong answers by eye.
+//!
+//! Step-by-step simulations become sequences of PPM frames, everything else a single SVG. The
+//! solvers report their intermediate steps through observers, so the pictures show exactly what
+//! the solutions compute.
+
+pub mod ppm;
+pub mod svg;
+
+use anyhow::{Result, bail};
+
+use crate::{
+    day4, day7, day8, day9, day12,
+    utils::grid::Grid,
+    visualize::{
+        ppm::{ACCENT, BACKGROUND, Color, FOREGROUND, HIGHLIGHT},
+        svg::Svg,
+    },
+};
+
+const PALETTE: [&str; 6] = [
+    "#e6463c", "#fad250", "#50b4fa", "#78dc78", "#c882f0", "#f0a050",
+];
+
+pub enum Picture {
+    Svg(Svg),
+    Frames(Vec<Grid<Color>>),
+}
+
+/// Draws the solution of `day` for `input`.
+pub fn visualize(day: u8, input: &str) -> Result<Picture> {
+    Ok(match day {
+        4 => Picture::Frames(rolls(&day4::parse(input)?)),
+        7 => Picture::Frames(beams(&day7::parse(input)?)),
+        8 => Picture::Svg(circuits(&day8::parse(input)?)),
+        9 => Picture::Svg(tiles(&day9::parse(input)?)),
+        12 => Picture::Svg(regions(&day12::parse(input)?)),
+        _ => bail!("No visualization for day {day}"),
+    })
+}
+
+/// One frame per round of removals, with the removed rolls highlighted, and the final state.
+pub fn rolls(grid: &Grid<bool>) -> Vec<Grid<Color>> {
+    let draw = |grid: &Grid<bool>| grid.map(|&roll| if roll { FOREGROUND } else { BACKGROUND });
+    let mut frames = Vec::new();
+    let mut last = grid.clone();
+    day4::part2_observed(grid, |round| {
+        let mut frame = draw(round.grid);
+        for &roll in round.removed {
+            frame[roll] = HIGHLIGHT;
+        }
+        frames.push(frame);
+        last = round.grid.clone();
+        for &roll in round.removed {
+            last[roll] = false;
+        }
+    });
+    frames.push(draw(&last));
+    frames
+}
+
+/// One frame per row of the manifold, with the beams drawn down to that row.
+pub fn beams(manifold: &Grid<day7::Cell>) -> Vec<Grid<Color>> {
+    let mut frame = manifold.map(|cell| match cell {
+        day7::Cell::Empty => BACKGROUND,
+        day7::Cell::Start => HIGHLIGHT,
+        day7::Cell::Splitter => ACCENT,
+    });
+    let mut frames = vec![frame.clone()];
+    day7::part1_observed(manifold, |beams| {
+        for &tachyon in beams.tachyons {
+            if let Some(cell) = frame.get_mut((tachyon, beams.depth)) {
+                *cell = FOREGROUND;
+            }
+        }
+        frames.push(frame.clone());
+    });
+    frames
+}
+
+/// The junction boxes seen from above, with every cable that joins two circuits.
+///
+/// The cable that finally joins everything into one circuit is highlighted.
+pub fn circuits(boxes: &[day8::JunctionBox]) -> Svg {
+    let points = boxes
+        .iter()
+        .map(|junction_box| {
+            let (x, y, _) = junction_box.position();
+            (x as f64, y as f64)
+        })
+        .collect::<Vec<_>>();
+    let mut svg = bounds(&points, 0.0);
+    let mut cables = Vec::new();
+    day8::part2_observed(boxes, |connection| cables.push(connection.boxes));
+    for (pos, &(from, to)) in cables.iter().enumerate() {
+        let (stroke, width) = if pos + 1 == cables.len() {
+            (PALETTE[0], 3.0)
+        } else {
+            (PALETTE[2], 1.0)
+        };
+        svg.line(points[from], points[to], stroke, width);
+    }
+    for &point in &points {
+        svg.dot(point, 3.0, PALETTE[1]);
+    }
+    svg
+}
+
+/// The loop of red tiles enclosing the green ones, with every new largest rectangle outlined and
+/// the final one filled.
+pub fn tiles(red_tiles: &[(isize, isize)]) -> Svg {
+    let center = |&(x, y): &(isize, isize)| (x as f64 + 0.5, y as f64 + 0.5);
+    let points = red_tiles.iter().map(center).collect::<Vec<_>>();
+    let mut svg = bounds(&points, 0.5);
+    svg.polygon(&points, "#2d5a2d", PALETTE[3]);
+    let mut candidates = Vec::new();
+    // Without any rectangle, only the loop is drawn.
+    day9::part2_observed(red_tiles, |candidate| candidates.push(candidate.corners));
+    for (pos, corners) in candidates.iter().enumerate() {
+        let [(x1, y1), (x2, y2)] = corners.map(|corner| center(&corner));
+        let origin = (x1.min(x2), y1.min(y2));
+        let size = ((x1 - x2).abs(), (y1 - y2).abs());
+        if pos + 1 == candidates.len() {
+            svg.rect(origin, size, "#e6463c80", PALETTE[0]);
+        } else {
+            svg.rect(origin, size, "none", PALETTE[1]);
+        }
+    }
+    for &point in &points {
+        svg.dot(point, 2.0, PALETTE[0]);
+    }
+    svg
+}
+
+/// Every region with as many of its presents as there are whole 3x3 slots, outlined in green if
+/// part 1 finds that all of them fit and in red if it does not. A region that only fits by a
+/// tighter packing shows fewer presents than it holds.
+pub fn regions(input: &day12::Input) -> Svg {
+    let (_, regions) = input;
+    let cell = |size: fn(&(usize, usize)) -> usize| {
+        regions
+            .iter()
+            .map(|(dimension, _)| size(dimension))
+            .max()
+            .unwrap_or(0) as f64
+            + 2.0
+    };
+    let (cell_width, cell_height) = (cell(|(x, _)| *x), cell(|(_, y)| *y));
+    let columns = (regions.len() as f64).sqrt().ceil().max(1.0) as usize;
+    let rows = regions.len().div_ceil(columns);
+    let mut svg = Svg::new(
+        0.0,
+        0.0,
+        columns as f64 * cell_width,
+        rows as f64 * cell_height,
+    );
+    let mut index = 0;
+    day12::part1_observed(input, |region| {
+        let origin = (
+            (index % columns) as f64 * cell_width + 1.0,
+            (index / columns) as f64 * cell_height + 1.0,
+        );
+        index += 1;
+        let (width, height) = region.size;
+        let slots_per_row = width / 3;
+        let presents = region
+            .presents
+            .iter()
+            .enumerate()
+            .flat_map(|(shape, &count)| std::iter::repeat_n(shape, count));
+        for (slot, shape) in presents.take(slots_per_row * (height / 3)).enumerate() {
+            let slot_origin = (
+                origin.0 + (slot % slots_per_row * 3) as f64,
+                origin.1 + (slot / slots_per_row * 3) as f64,
+            );
+            svg.rect(
+                slot_origin,
+                (3.0, 3.0),
+                PALETTE[shape % PALETTE.len()],
+                "#181820",
+            );
+        }
+        let outline = if region.fits { PALETTE[3] } else { PALETTE[0] };
+        svg.rect(origin, (width as f64, height as f64), "none", outline);
+    });
+    svg
+}
+
+/// A scene around `points` with `margin` to spare on every side.
+fn bounds(points: &[(f64, f64)], margin: f64) -> Svg {
+    if points.is_empty() {
+        return Svg::new(0.0, 0.0, 1.0, 1.0);
+    }
+    let (min_x, max_x) = points
+        .iter()
+        .map(|point| point.0)
+        .fold((f64::MAX, f64::MIN), |(min, max), x| {
+            (min.min(x), max.max(x))
+        });
+    let (min_y, max_y) = points
+        .iter()
+        .map(|point| point.1)
+        .fold((f64::MAX, f64::MIN), |(min, max), y| {
+            (min.min(y), max.max(y))
+        });
+    let margin = margin.max((max_x - min_x).max(max_y - min_y) * 0.02);
+    Svg::new(
+        min_x - margin,
+        min_y - margin,
+        max_x - min_x + 2.0 * margin,
+        max_y - min_y + 2.0 * margin,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_follow_the_simulation() {
+        let grid = day4::parse("@@@\n@@@\n@@@").unwrap();
+        let frames = rolls(&grid);
+        // The corners go first, then the edges and at last the center.
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0][(0, 0)], HIGHLIGHT);
+        assert_eq!(frames[0][(1, 0)], FOREGROUND);
+        assert_eq!(frames[1][(1, 0)], HIGHLIGHT);
+        assert_eq!(frames[2][(1, 1)], HIGHLIGHT);
+        assert!(frames[3].iter().all(|(_, &color)| color == BACKGROUND));
+
+        let manifold = day7::parse("..S..\n.....\n..^..\n.....").unwrap();
+        let frames = beams(&manifold);
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[3][(1, 3)], FOREGROUND);
+        assert_eq!(frames[3][(2, 2)], ACCENT);
+    }
+
+    #[test]
+    fn scenes_show_the_answer() {
+        let tiles =
+            tiles(&day9::parse("7,1\n11,1\n11,7\n9,7\n9,5\n2,5\n2,3\n7,3").unwrap()).to_string();
+        assert_eq!(tiles.matches("<polygon").count(), 1);
+        // Only the largest rectangle is filled.
+        assert_eq!(tiles.matches(r##"fill="#e6463c80""##).count(), 1);
+        let single = super::tiles(&day9::parse("3,4").unwrap()).to_string();
+        assert_eq!(single.matches("<polygon").count(), 1);
+        assert_eq!(single.matches("<rect").count(), 0);
+
+        let boxes = day8::parse("0,0,0\n1,0,0\n5,5,5").unwrap();
+        assert_eq!(circuits(&boxes).to_string().matches("<line").count(), 2 + 3);
+    }
+}
@@ -0,0 +1,46 @@
+use crate::utils::grid::Grid;
+
+pub type Color = [u8; 3];
+
+pub const BACKGROUND: Color = [24, 24, 32];
+pub const FOREGROUND: Color = [200, 200, 210];
+pub const HIGHLIGHT: Color = [230, 70, 60];
+pub const ACCENT: Color = [250, 210, 80];
+
+/// Encodes `image` as a binary PPM, drawing every cell as a `scale` by `scale` square.
+pub fn encode(image: &Grid<Color>, scale: usize) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (width, height) = (image.width() * scale, image.height() * scale);
+    let mut ppm = format!("P6\n{width} {height}\n255\n").into_bytes();
+    ppm.reserve(width * height * 3);
+    for row in image.rows().take(image.height()) {
+        let line = row
+            .iter()
+            .flat_map(|color| std::iter::repeat_n(color, scale))
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+        for _ in 0..scale {
+            ppm.extend(&line);
+        }
+    }
+    ppm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_scaled_pixels() {
+        let image = Grid::new(2, 1, vec![BACKGROUND, HIGHLIGHT]);
+        let ppm = encode(&image, 2);
+        let header = b"P6\n4 2\n255\n";
+        assert!(ppm.starts_with(header));
+        assert_eq!(ppm.len(), header.len() + 4 * 2 * 3);
+        assert_eq!(
+            &ppm[header.len()..][6..12],
+            [HIGHLIGHT, HIGHLIGHT].as_flattened()
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::{self, Write};
+
+use itertools::Itertools;
+
+/// A vector scene in user coordinates, with strokes measured in pixels however large the
+/// coordinates get.
+#[derive(Clone, Debug)]
+pub struct Svg {
+    view: [f64; 4],
+    elements: String,
+}
+
+impl Svg {
+    /// Creates a scene showing the area from `(x, y)` of the given size.
+    pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            view: [x, y, width.max(1.0), height.max(1.0)],
+            elements: String::new(),
+        }
+    }
+
+    fn element(&mut self, element: fmt::Arguments) {
+        writeln!(self.elements, "  {element}").unwrap();
+    }
+
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), stroke: &str, width: f64) {
+        self.element(format_args!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{stroke}" stroke-width="{width}" vector-effect="non-scaling-stroke"/>"#,
+            from.0, from.1, to.0, to.1
+        ));
+    }
+
+    pub fn rect(
+        &mut self,
+        (x, y): (f64, f64),
+        (width, height): (f64, f64),
+        fill: &str,
+        stroke: &str,
+    ) {
+        self.element(format_args!(
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{fill}" stroke="{stroke}" vector-effect="non-scaling-stroke"/>"#
+        ));
+    }
+
+    pub fn polygon(&mut self, points: &[(f64, f64)], fill: &str, stroke: &str) {
+        let points = points.iter().map(|(x, y)| format!("{x},{y}")).join(" ");
+        self.element(format_args!(
+            r#"<polygon points="{points}" fill="{fill}" stroke="{stroke}" vector-effect="non-scaling-stroke"/>"#
+        ));
+    }
+
+    /// A dot with a radius of `radius` pixels.
+    pub fn dot(&mut self, (x, y): (f64, f64), radius: f64, fill: &str) {
+        self.element(format_args!(
+            r#"<line x1="{x}" y1="{y}" x2="{x}" y2="{y}" stroke="{fill}" stroke-width="{}" stroke-linecap="round" vector-effect="non-scaling-stroke"/>"#,
+            2.0 * radius
+        ));
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y, width, height] = self.view;
+        let scale = 800.0 / width.max(height);
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{x} {y} {width} {height}" width="{}" height="{}" style="background: #181820">"#,
+            (width * scale).round(),
+            (height * scale).round()
+        )?;
+        write!(f, "{}</svg>", self.elements)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_document() {
+        let mut svg = Svg::new(0.0, 0.0, 10.0, 5.0);
+        svg.rect((1.0, 1.0), (2.0, 3.0), "red", "none");
+        svg.polygon(&[(0.0, 0.0), (1.0, 0.5)], "none", "white");
+        let svg = svg.to_string();
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 5" width="800" height="400""#
+        ));
+        assert!(svg.contains(r#"<rect x="1" y="1" width="2" height="3" fill="red""#));
+        assert!(svg.contains(r#"points="0,0 1,0.5""#));
+        assert!(svg.ends_with("</svg>"));
+    }
+}